@@ -1,4 +1,4 @@
-use libcalcore::express::error::CalError;
+use libcalcore::express::{error::CalError, parser::parse};
 use slint::SharedString;
 
 slint::include_modules!();
@@ -91,7 +91,7 @@ fn main() {
                     Ok(res) => app.set_result(SharedString::from(res.to_string())),
                     Err(err) => {
                         app.set_message_title(SharedString::from("Error"));
-                        app.set_message_content(SharedString::from(err.to_string()));
+                        app.set_message_content(SharedString::from(err.render(&exp_str)));
                     }
                 }
             },
@@ -106,10 +106,8 @@ fn main() {
 }
 
 fn calculate_express(express: &str) -> Result<f64, CalError> {
-    use libcalcore::express::parser::lookahead::LookAhead;
-    let mut parser = LookAhead::try_from(express)?;
-    let ast = parser.parse_expr()?;
-    Ok(ast.eval().unwrap())
+    let ast = parse(express)?;
+    ast.eval()
 }
 
 fn move_cursor_pos(str: &String, pos: i32, offset: i32) -> String {
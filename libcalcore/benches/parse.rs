@@ -0,0 +1,53 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use libcalcore::express::{
+    env::Environment,
+    lexer::tokenize,
+    lexer::Token,
+    parser::{parse, Tree},
+};
+
+/// `n` terms like `1+2*3-4/5+...`, cycling through the operators of both parsers.
+fn generate(n: usize) -> String {
+    let mut src = String::from("1");
+    for i in 1..n {
+        src.push(['+', '*', '-', '/', '%'][i % 5]);
+        src.push_str(&(i % 9 + 1).to_string());
+    }
+    src
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    for n in [1_000, 2_000, 4_000, 8_000, 16_000] {
+        let src = generate(n);
+        let tokens: Vec<Token> = tokenize(&src).unwrap();
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("tree", n), &tokens, |b, tokens| {
+            b.iter(|| Tree::parse(black_box(tokens.clone())).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("lookahead", n), &src, |b, src| {
+            b.iter(|| parse(black_box(src)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+/// The legacy tree next to the AST of `parse`, which is what replaces it.
+fn bench_calculate(c: &mut Criterion) {
+    let mut group = c.benchmark_group("calculate");
+    let env = Environment::<f64>::with_builtins();
+    for n in [1_000, 4_000, 16_000] {
+        let src = generate(n);
+        let tree = Tree::parse(tokenize(&src).unwrap()).unwrap();
+        let ast = parse(&src).unwrap();
+        group.throughput(Throughput::Elements(n as u64));
+        group.bench_with_input(BenchmarkId::new("tree", n), &tree, |b, tree| b.iter(|| tree.calculate().unwrap()));
+        group.bench_with_input(BenchmarkId::new("lookahead", n), &ast, |b, ast| {
+            b.iter(|| ast.eval_with(black_box(&env)).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse, bench_calculate);
+criterion_main!(benches);
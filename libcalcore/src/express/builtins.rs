@@ -0,0 +1,140 @@
+use std::cmp::Ordering;
+
+use bigdecimal::BigDecimal;
+
+use super::{
+    calendar::{self, SECONDS_PER_DAY},
+    error::EvalError,
+    function::{Arity, FunctionRegistry},
+    number::{Measures, Numeric},
+    operator::{Assoc, OperatorTable},
+    unit::Unit,
+};
+
+type Unary<N> = fn(&N) -> Result<N, EvalError>;
+
+/// Register the standard library: roots, trigonometry, logarithms, rounding,
+/// factorials, `min`/`max`, and business days where `N` has dates.
+pub fn register<N: Numeric>(registry: &mut FunctionRegistry<N>) {
+    let unary: [(&str, &str, Unary<N>); 10] = [
+        ("sqrt", "sqrt(x): square root of x", N::sqrt),
+        ("exp", "exp(x): e raised to x", N::exp),
+        ("sin", "sin(x): sine of x radians", N::sin),
+        ("cos", "cos(x): cosine of x radians", N::cos),
+        ("tan", "tan(x): tangent of x radians", N::tan),
+        ("ln", "ln(x): natural logarithm of x", N::ln),
+        ("abs", "abs(x): absolute value of x", N::abs),
+        ("floor", "floor(x): largest integer not above x", N::floor),
+        ("ceil", "ceil(x): smallest integer not below x", N::ceil),
+        ("round", "round(x): nearest integer, halves away from zero", N::round),
+    ];
+    for (name, doc, func) in unary {
+        registry.register_numeric_fn(name, Arity::exact(1), doc, move |args| func(&args[0]));
+    }
+
+    registry.register_numeric_fn(
+        "log",
+        Arity::range(1, 2),
+        "log(x), log(x, b): common logarithm of x, or logarithm to base b",
+        |args| match args.get(1) {
+            Some(base) => args[0].ln()?.div(&base.ln()?),
+            None => args[0].log10(),
+        },
+    );
+    registry.register_numeric_fn("factorial", Arity::exact(1), "factorial(n): 1*2*...*n", |args| {
+        args[0].factorial()
+    });
+    registry.register_numeric_fn(
+        "binomial",
+        Arity::exact(2),
+        "binomial(n, k): the number of ways to choose k of n items",
+        |args| args[0].binomial(&args[1]),
+    );
+    registry.register_numeric_fn("min", Arity::at_least(1), "min(x, ...): smallest argument", |args| {
+        extreme(args, Ordering::Less)
+    });
+    registry.register_numeric_fn("max", Arity::at_least(1), "max(x, ...): largest argument", |args| {
+        extreme(args, Ordering::Greater)
+    });
+
+    if let Some(measures) = N::measures() {
+        register_dates(registry, measures);
+    }
+}
+
+/// Register the business day functions, for representations with dates.
+fn register_dates<N: Numeric>(registry: &mut FunctionRegistry<N>, measures: Measures<N>) {
+    registry.register_numeric_fn(
+        "weekday",
+        Arity::exact(1),
+        "weekday(d): day of the week of date d, 1 for Monday to 7 for Sunday",
+        move |args| {
+            let days = day_of(&measures, &args[0])?;
+            N::from_f64(f64::from(calendar::weekday(days)), args[0].mode())
+        },
+    );
+    registry.register_numeric_fn(
+        "workday",
+        Arity::exact(2),
+        "workday(d, n): the date n business days after date d, skipping weekends",
+        move |args| {
+            // a count, so `workday(d, 2 days)` is not 172800 business days
+            let n = (measures.convert)(&args[1], &Unit::one())?.to_f64();
+            let days = day_of(&measures, &args[0])?;
+            if n.fract() != 0.0 || n.abs() > 1e9 {
+                return Err(EvalError::NotInteger);
+            }
+            (measures.from_date)(calendar::add_workdays(days, n as i64) * SECONDS_PER_DAY, args[0].mode())
+        },
+    );
+    registry.register_numeric_fn(
+        "workdays",
+        Arity::exact(2),
+        "workdays(a, b): the number of business days after date a up to date b",
+        move |args| {
+            let count = calendar::workdays_between(day_of(&measures, &args[0])?, day_of(&measures, &args[1])?);
+            N::from_f64(count as f64, args[0].mode())
+        },
+    );
+}
+
+/// Register the arithmetic operators, from `±` binding tightest down to `+` and `-`.
+pub fn register_operators<N: Numeric>(table: &mut OperatorTable<N>) {
+    table.register_infix("+", 5, Assoc::Left, "a + b: sum", N::add);
+    table.register_infix("-", 5, Assoc::Left, "a - b: difference", N::sub);
+    table.register_infix("*", 10, Assoc::Left, "a * b: product", N::mul);
+    table.register_infix("/", 10, Assoc::Left, "a / b: quotient", N::div);
+    table.register_infix("%", 10, Assoc::Left, "a % b: remainder of a divided by b", N::rem);
+    table.register_infix("^", 15, Assoc::Right, "a ^ b: a raised to b", N::pow);
+    // binds like part of a literal, so `2*3±1` is `2*(3±1)`
+    table.register_infix("±", 20, Assoc::Left, "a ± b: a with an uncertainty of b", N::plus_minus);
+
+    // a sign binds below `^`, so `-2^2` is `-(2^2)`
+    table.register_prefix("-", 15, "-a: negation", N::neg);
+    table.register_prefix("+", 15, "+a: a itself", |a| Ok(a.clone()));
+    table.register_prefix("√", i8::MAX, "√a: square root of a", N::sqrt);
+
+    table.register_postfix("!", "n!: factorial", N::factorial);
+    table.register_postfix("!!", "n!!: double factorial, every other integer from n down", N::double_factorial);
+    table.register_postfix("%", "a%: a hundredth of a, read in calculator mode only", |a| {
+        a.div(&N::from_literal(&BigDecimal::from(100), a.mode())?)
+    });
+}
+
+/// The day of a date, ignoring the time.
+fn day_of<N: Numeric>(measures: &Measures<N>, date: &N) -> Result<i64, EvalError> {
+    Ok((measures.to_date)(date)?.div_euclid(SECONDS_PER_DAY))
+}
+
+/// The argument that compares as `wanted` against all others.
+fn extreme<N: Numeric>(args: &[N], wanted: Ordering) -> Result<N, EvalError> {
+    let mut best = &args[0];
+    for arg in &args[1..] {
+        match arg.compare(best) {
+            Some(ord) if ord == wanted => best = arg,
+            Some(_) => {}
+            None => return Err(EvalError::NotFinite),
+        }
+    }
+    Ok(best.clone())
+}
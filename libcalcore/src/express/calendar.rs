@@ -0,0 +1,113 @@
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+pub fn is_leap_year(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// The day number of a date, or `None` if there is no such date.
+///
+/// Days are counted from 1970-01-01 in the proleptic Gregorian calendar, without
+/// time zones, so date arithmetic never depends on where or when it runs.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+    if !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return None;
+    }
+    // years start in March, so the leap day is the last day of the year
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month = i64::from(month);
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + i64::from(day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    Some(era * 146_097 + day_of_era - 719_468)
+}
+
+/// The year, month and day of a day number.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// The ISO weekday of a day number, 1 for Monday up to 7 for Sunday.
+pub fn weekday(days: i64) -> u32 {
+    // 1970-01-01 was a Thursday
+    (days + 3).rem_euclid(7) as u32 + 1
+}
+
+/// Whether the day is a business day, Monday to Friday.
+pub fn is_workday(days: i64) -> bool {
+    weekday(days) <= 5
+}
+
+/// The business day `n` business days after `days`, or before it for negative `n`.
+/// A start on a weekend counts from the business day before it, so one business
+/// day after a Saturday is the Monday.
+pub fn add_workdays(days: i64, n: i64) -> i64 {
+    if n == 0 {
+        return days;
+    }
+    let step = n.signum();
+    let mut day = days;
+    while !is_workday(day) {
+        day -= step;
+    }
+    // a whole week of business days lands on the same weekday
+    day += n / 5 * 7;
+    let mut left = (n % 5).abs();
+    while left > 0 {
+        day += step;
+        if is_workday(day) {
+            left -= 1;
+        }
+    }
+    day
+}
+
+/// The number of business days after `from` up to and including `to`,
+/// negative if `to` is before `from`.
+pub fn workdays_between(from: i64, to: i64) -> i64 {
+    // business days before `days`, counted from the Monday of 1969-12-29
+    let before = |days: i64| {
+        let since_monday = days + 3;
+        since_monday.div_euclid(7) * 5 + since_monday.rem_euclid(7).min(5)
+    };
+    before(to + 1) - before(from + 1)
+}
+
+/// `YYYY-MM-DD`, followed by `HH:MM` or `HH:MM:SS` if the time is not midnight.
+pub fn format_date(seconds: i64) -> String {
+    let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+    let date = format!("{year:04}-{month:02}-{day:02}");
+    match seconds.rem_euclid(SECONDS_PER_DAY) {
+        0 => date,
+        time => format!("{date} {}", format_clock(time)),
+    }
+}
+
+/// `HH:MM`, or `HH:MM:SS` if there are seconds, with as many hours as needed.
+pub fn format_clock(seconds: i64) -> String {
+    let sign = if seconds < 0 { "-" } else { "" };
+    let seconds = seconds.unsigned_abs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    match seconds {
+        0 => format!("{sign}{hours:02}:{minutes:02}"),
+        _ => format!("{sign}{hours:02}:{minutes:02}:{seconds:02}"),
+    }
+}
@@ -0,0 +1,162 @@
+use std::{collections::HashMap, fs, path::Path, str::FromStr};
+
+use bigdecimal::BigDecimal;
+use num_rational::BigRational;
+use num_traits::{One, Signed};
+
+use super::{
+    error::{EvalError, RateError},
+    unit::{decimal_to_rational, Unit},
+};
+
+/// The number of decimals of the ISO 4217 currencies that do not use cents.
+const MINOR_UNITS: [(&str, u32); 13] = [
+    ("BHD", 3),
+    ("CLP", 0),
+    ("IQD", 3),
+    ("ISK", 0),
+    ("JOD", 3),
+    ("JPY", 0),
+    ("KRW", 0),
+    ("KWD", 3),
+    ("LYD", 3),
+    ("OMR", 3),
+    ("PYG", 0),
+    ("TND", 3),
+    ("VND", 0),
+];
+
+/// The decimals amounts of `code` are rounded to, 2 unless the currency has no cents.
+pub fn minor_units(code: &str) -> u32 {
+    MINOR_UNITS
+        .iter()
+        .find_map(|(c, decimals)| (*c == code).then_some(*decimals))
+        .unwrap_or(2)
+}
+
+/// Whether `name` has the shape of an ISO 4217 code, three capital letters like `EUR`.
+pub fn is_currency_code(name: &str) -> bool {
+    name.len() == 3 && name.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+#[derive(Debug, Clone)]
+struct Rate {
+    rate: BigRational,
+    decimals: u32,
+}
+
+/// Exchange rates to one base currency, supplied by the user rather than fetched.
+///
+/// The table is read from text with one currency per line, giving the value of one
+/// unit of it in the base currency and optionally the decimals to round it to:
+///
+/// ```text
+/// # rates of 2024-05-01
+/// base USD
+/// EUR 1.0712
+/// JPY 0.00634
+/// BHD 2.6525 3
+/// ```
+#[derive(Debug, Clone)]
+pub struct RateTable {
+    base: String,
+    rates: HashMap<String, Rate>,
+}
+
+/// A table of US dollars alone, so other currencies fail until their rates are set.
+impl Default for RateTable {
+    fn default() -> Self {
+        Self::new("USD")
+    }
+}
+
+impl RateTable {
+    /// A table without rates, where only `base` can be used.
+    pub fn new(base: &str) -> Self {
+        let mut rates = HashMap::new();
+        let rate = Rate {
+            rate: BigRational::one(),
+            decimals: minor_units(base),
+        };
+        rates.insert(String::from(base), rate);
+        Self {
+            base: String::from(base),
+            rates,
+        }
+    }
+
+    pub fn base(&self) -> &str {
+        &self.base
+    }
+
+    /// Set one unit of `code` to be worth `rate` of the base currency, which must be positive.
+    pub fn set_rate(&mut self, code: &str, rate: &BigDecimal) -> Result<(), RateError> {
+        self.set_rate_with_decimals(code, rate, minor_units(code))
+    }
+
+    /// Like [`RateTable::set_rate`], rounding amounts of `code` to `decimals` places.
+    pub fn set_rate_with_decimals(&mut self, code: &str, rate: &BigDecimal, decimals: u32) -> Result<(), RateError> {
+        if !rate.is_positive() {
+            return Err(RateError::NotPositive(String::from(code)));
+        }
+        let rate = Rate {
+            rate: decimal_to_rational(rate),
+            decimals,
+        };
+        self.rates.insert(String::from(code), rate);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, code: &str) -> bool {
+        code != self.base && self.rates.remove(code).is_some()
+    }
+
+    /// The currency `code` as a unit of the base currency.
+    ///
+    /// Fails with [`EvalError::MissingRate`] for currency codes without a rate, and
+    /// [`EvalError::UnknownUnit`] for anything else.
+    pub fn get(&self, code: &str) -> Result<Unit, EvalError> {
+        match self.rates.get(code) {
+            Some(Rate { rate, decimals }) => Ok(Unit::currency(code, rate.clone(), *decimals)),
+            None if is_currency_code(code) => Err(EvalError::MissingRate(String::from(code))),
+            None => Err(EvalError::UnknownUnit(String::from(code))),
+        }
+    }
+
+    pub fn contains(&self, code: &str) -> bool {
+        self.rates.contains_key(code)
+    }
+
+    /// Parse a table in the format described on [`RateTable`].
+    pub fn parse(src: &str) -> Result<Self, RateError> {
+        let mut table: Option<Self> = None;
+        for (index, line) in src.lines().enumerate() {
+            let syntax_error = || RateError::Syntax {
+                line: index + 1,
+                text: String::from(line),
+            };
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            match fields[..] {
+                [] => {}
+                ["base", code] if table.is_none() && is_currency_code(code) => table = Some(Self::new(code)),
+                [code, rate, ref decimals @ ..] if decimals.len() <= 1 && is_currency_code(code) => {
+                    let table = table.as_mut().ok_or(RateError::MissingBase)?;
+                    let rate = BigDecimal::from_str(rate).map_err(|_| syntax_error())?;
+                    let decimals = match decimals.first() {
+                        Some(decimals) => decimals.parse().map_err(|_| syntax_error())?,
+                        None => minor_units(code),
+                    };
+                    table.set_rate_with_decimals(code, &rate, decimals).map_err(|_| syntax_error())?;
+                }
+                _ => return Err(syntax_error()),
+            }
+        }
+        table.ok_or(RateError::MissingBase)
+    }
+
+    /// Read a table from a local file in the format described on [`RateTable`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, RateError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+}
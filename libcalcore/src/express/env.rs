@@ -0,0 +1,256 @@
+use std::{collections::HashMap, rc::Rc, str::FromStr};
+
+use bigdecimal::BigDecimal;
+
+use super::{
+    currency::RateTable,
+    error::EvalError,
+    function::{Arity, Callable, FunctionRegistry},
+    number::{Number, Numeric},
+    operator::OperatorTable,
+    parser::lookahead::Expr,
+    unit::{Unit, UnitTable},
+};
+
+/// How deep user-defined functions may call each other before evaluation gives up.
+pub const DEFAULT_MAX_DEPTH: usize = 256;
+
+const PI: &str = "3.14159265358979323846264338327950288419716939937510582097494459";
+const E: &str = "2.71828182845904523536028747135266249775724709369995957496696763";
+
+/// The variables and functions visible to an expression while it is evaluated.
+///
+/// A new environment already knows the constants `pi` and `e` and the built-in functions.
+/// Values are of the [`Numeric`] type `N`, by default the runtime-switchable [`Number`].
+#[derive(Debug, Clone)]
+pub struct Environment<N: Numeric = Number> {
+    vars: HashMap<String, N>,
+    /// Exact values converted in the current mode when read, unless a variable of the same name hides them
+    constants: HashMap<String, BigDecimal>,
+    mode: N::Mode,
+    registry: FunctionRegistry<N>,
+    operators: OperatorTable<N>,
+    functions: HashMap<String, Rc<Function>>,
+    units: UnitTable,
+    rates: RateTable,
+    max_depth: usize,
+}
+
+/// A function defined by `name(param, ...) = body`.
+#[derive(Debug)]
+pub struct Function {
+    params: Vec<String>,
+    body: Expr,
+}
+
+impl Function {
+    pub fn new(params: Vec<String>, body: Expr) -> Self {
+        Self { params, body }
+    }
+
+    pub fn params(&self) -> &[String] {
+        &self.params
+    }
+
+    pub fn body(&self) -> &Expr {
+        &self.body
+    }
+
+    pub fn arity(&self) -> Arity {
+        Arity::exact(self.params.len())
+    }
+}
+
+impl<N: Numeric> Default for Environment<N> {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+impl Environment {
+    pub fn new() -> Self {
+        Self::with_builtins()
+    }
+
+    /// An environment without any predefined constants or functions.
+    pub fn empty() -> Self {
+        Self::with_registry(FunctionRegistry::new())
+    }
+}
+
+impl<N: Numeric> Environment<N> {
+    /// The constants `pi` and `e` and the built-in functions, computing with `N`.
+    pub fn with_builtins() -> Self {
+        let mut env = Self::with_registry(FunctionRegistry::with_builtins());
+        for (name, digits) in [("pi", PI), ("e", E)] {
+            env.set_constant(name, BigDecimal::from_str(digits).expect("valid constant"));
+        }
+        env
+    }
+
+    /// An environment without predefined constants, calling into `registry`.
+    pub fn with_registry(registry: FunctionRegistry<N>) -> Self {
+        Self {
+            vars: HashMap::new(),
+            constants: HashMap::new(),
+            mode: N::Mode::default(),
+            registry,
+            operators: OperatorTable::with_builtins(),
+            functions: HashMap::new(),
+            units: UnitTable::default(),
+            rates: RateTable::default(),
+            max_depth: DEFAULT_MAX_DEPTH,
+        }
+    }
+
+    /// Bind `name` to `value`, returning the value it was bound to before.
+    pub fn set<V: Into<N>>(&mut self, name: &str, value: V) -> Option<N> {
+        self.vars.insert(String::from(name), value.into())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&N> {
+        self.vars.get(name)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<N> {
+        self.vars.remove(name)
+    }
+
+    /// Define `name` as `value`, which expressions read in whatever mode is current then.
+    pub fn set_constant(&mut self, name: &str, value: BigDecimal) -> Option<BigDecimal> {
+        self.constants.insert(String::from(name), value)
+    }
+
+    pub fn constant(&self, name: &str) -> Option<&BigDecimal> {
+        self.constants.get(name)
+    }
+
+    pub fn mode(&self) -> N::Mode {
+        self.mode
+    }
+
+    /// Choose how numbers are represented. Variables bound in another mode
+    /// and the constants are converted when they are read.
+    pub fn set_mode(&mut self, mode: N::Mode) {
+        self.mode = mode;
+    }
+
+    pub fn registry(&self) -> &FunctionRegistry<N> {
+        &self.registry
+    }
+
+    pub fn registry_mut(&mut self) -> &mut FunctionRegistry<N> {
+        &mut self.registry
+    }
+
+    /// The operators expressions evaluated here may use, see [`OperatorTable::grammar`] to parse them.
+    pub fn operators(&self) -> &OperatorTable<N> {
+        &self.operators
+    }
+
+    pub fn operators_mut(&mut self) -> &mut OperatorTable<N> {
+        &mut self.operators
+    }
+
+    /// Shorthand for [`FunctionRegistry::register`] on this environment's registry.
+    pub fn register<C: Callable<N> + 'static>(&mut self, callable: C) -> Option<Rc<dyn Callable<N>>> {
+        self.registry.register(callable)
+    }
+
+    /// Define (or redefine) a function. Functions of the registry cannot be redefined.
+    pub fn define(&mut self, name: &str, function: Function) -> Result<Option<Rc<Function>>, EvalError> {
+        if self.registry.contains(name) {
+            return Err(EvalError::ShadowsBuiltin(String::from(name)));
+        }
+        Ok(self.functions.insert(String::from(name), Rc::new(function)))
+    }
+
+    pub fn function(&self, name: &str) -> Option<Rc<Function>> {
+        self.functions.get(name).cloned()
+    }
+
+    /// The units that can follow a number, as in `3 km`, or be converted to with `to`.
+    pub fn units(&self) -> &UnitTable {
+        &self.units
+    }
+
+    pub fn units_mut(&mut self) -> &mut UnitTable {
+        &mut self.units
+    }
+
+    /// The exchange rates of currencies, as in `12.50 USD to EUR`.
+    pub fn rates(&self) -> &RateTable {
+        &self.rates
+    }
+
+    pub fn rates_mut(&mut self) -> &mut RateTable {
+        &mut self.rates
+    }
+
+    pub fn set_rates(&mut self, rates: RateTable) {
+        self.rates = rates;
+    }
+
+    /// The unit called `name`, from the unit table or else the exchange rates.
+    pub fn unit(&self, name: &str) -> Result<Unit, EvalError> {
+        match self.units.get(name) {
+            Some(unit) => Ok(unit),
+            None => self.rates.get(name),
+        }
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    pub fn set_max_depth(&mut self, max_depth: usize) {
+        self.max_depth = max_depth;
+    }
+}
+
+/// Where names are resolved during evaluation: first the parameters of the
+/// function being called, then the globals of the environment.
+pub(crate) struct Scope<'a, N: Numeric> {
+    env: &'a Environment<N>,
+    locals: HashMap<&'a str, N>,
+    depth: usize,
+}
+
+impl<'a, N: Numeric> Scope<'a, N> {
+    pub fn global(env: &'a Environment<N>) -> Self {
+        Self {
+            env,
+            locals: HashMap::new(),
+            depth: 0,
+        }
+    }
+
+    pub fn env(&self) -> &'a Environment<N> {
+        self.env
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The value `name` is bound to, a parameter, variable or constant, in the current mode.
+    pub fn get(&self, name: &str) -> Option<Result<N, EvalError>> {
+        let mode = self.env.mode();
+        match self.locals.get(name).or_else(|| self.env.get(name)) {
+            Some(value) => Some(value.to_mode(mode)),
+            None => self.env.constant(name).map(|value| N::from_literal(value, mode)),
+        }
+    }
+
+    /// The scope of a call binding `params` to `args`. The caller's locals are not visible.
+    pub fn enter<'b>(&self, params: &'b [String], args: Vec<N>) -> Scope<'b, N>
+    where
+        'a: 'b,
+    {
+        Scope {
+            env: self.env,
+            locals: params.iter().map(String::as_str).zip(args).collect(),
+            depth: self.depth + 1,
+        }
+    }
+}
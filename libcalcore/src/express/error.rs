@@ -1,46 +1,77 @@
-use std::{error::Error, fmt::Display};
+use std::{error::Error, fmt::Display, io};
+
+use super::{function::Arity, span::Span, unit::Dimension};
 
 #[derive(Debug)]
 pub enum CalError {
-    LexError(LexerError),
-    SyntaxError(String),
+    LexError(LexerError, Span),
+    SyntaxError(String, Span),
+    EvalError(EvalError, Span),
 }
 
 impl CalError {
-    pub fn lex<T>(le: LexerError) -> Result<T, Self> {
-        Err(Self::LexError(le))
+    pub fn lex<T>(le: LexerError, span: Span) -> Result<T, Self> {
+        Err(Self::LexError(le, span))
+    }
+
+    pub fn syn<T>(msg: &str, span: Span) -> Result<T, Self> {
+        Err(Self::SyntaxError(String::from(msg), span))
     }
 
-    pub fn syn<T>(msg: &str) -> Result<T, Self> {
-        Err(Self::SyntaxError(String::from(msg)))
+    pub fn eval<T>(ee: EvalError, span: Span) -> Result<T, Self> {
+        Err(Self::EvalError(ee, span))
+    }
+
+    /// The region of the source this error points at.
+    pub fn span(&self) -> Span {
+        match self {
+            CalError::LexError(_, span)
+            | CalError::SyntaxError(_, span)
+            | CalError::EvalError(_, span) => *span,
+        }
+    }
+
+    /// The error message followed by `src` with the offending region underlined.
+    pub fn render(&self, src: &str) -> String {
+        format!("{self}\n{}", self.span().underline(src))
     }
 }
 
 impl Display for CalError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            CalError::LexError(le) => {
+            CalError::LexError(le, _) => {
                 write!(f, "Lex error:")?;
                 le.fmt(f)
             },
-            CalError::SyntaxError(se) => {
+            CalError::SyntaxError(se, _) => {
                 write!(f, "Syntax error: {se}")
             },
+            CalError::EvalError(ee, _) => {
+                write!(f, "Eval error: {ee}")
+            },
         }
     }
 }
 
+impl Error for CalError {}
+
 
 #[derive(Debug)]
 pub enum LexerError {
-    InvalidNumber(String),
+    InvalidNumber(String, NumberError),
+    /// A date or time that does not exist, like `2026-02-30` or `17:75`
+    InvalidDate(String),
     UnknowChar(char),
 }
 
 impl Display for LexerError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let message = match self {
-            LexerError::InvalidNumber(fnum) => format!("Invalid number: '{}'! | TokenError::InvalidNumber", fnum),
+            LexerError::InvalidNumber(fnum, why) => {
+                format!("Invalid number: '{}', {}! | TokenError::InvalidNumber", fnum, why)
+            }
+            LexerError::InvalidDate(date) => format!("Invalid date: '{}'! | TokenError::InvalidDate", date),
             LexerError::UnknowChar(ch) => format!("Unknow char: '{}'! | TokenError::UnknowChar", ch),
         };
         write!(f, "{}", message)
@@ -49,6 +80,136 @@ impl Display for LexerError {
 
 impl Error for LexerError {}
 
+/// What is wrong with an invalid number literal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberError {
+    /// No digits at all, like `.` or `0x`
+    NoDigits,
+    /// A digit too large for the base, like the `2` of `0b102`
+    InvalidDigit { digit: char, radix: u32 },
+    /// A `_` that is not between two digits, like `1__000` or `1_`
+    Separator,
+    /// More than one decimal point, like `1.2.3`
+    ExtraPoint,
+    /// An exponent without digits, like `1e+`
+    MissingExponent,
+}
+
+impl Display for NumberError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumberError::NoDigits => write!(f, "no digits"),
+            NumberError::InvalidDigit { digit, radix } => write!(f, "'{digit}' is not a digit in base {radix}"),
+            NumberError::Separator => write!(f, "'_' must be between digits"),
+            NumberError::ExtraPoint => write!(f, "more than one decimal point"),
+            NumberError::MissingExponent => write!(f, "no digits in the exponent"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum EvalError {
+    UnboundVariable(String),
+    /// An operator that does not apply to its operands, e.g. `+` between two dates
+    InvalidOperator(String),
+    DivisionByZero,
+    /// An interval divisor containing zero, so the quotient is unbounded
+    StraddlesZero { lo: f64, hi: f64 },
+    /// The result has no finite value in the current number mode, e.g. `(-8)^0.5`
+    NotFinite,
+    /// A complex number where only real ones are allowed, e.g. `2i` outside the complex mode
+    NotReal,
+    /// A fraction where only integers are allowed, e.g. `7/2` with integers or `factorial(2.5)`
+    NotInteger,
+    /// The result would be too large to compute, e.g. `factorial(10^6)`
+    TooLarge,
+    /// Quantities of different dimensions combined, e.g. `1 m + 1 s`
+    DimensionMismatch { expected: Dimension, found: Dimension },
+    UnknownUnit(String),
+    /// A currency code without an exchange rate in the `RateTable`
+    MissingRate(String),
+    /// A unit or date used with a number type that has no dimensions
+    UnitsUnsupported,
+    /// A date function given something else, e.g. `weekday(5)`
+    NotADate,
+    UnknownFunction(String),
+    UnknownOperator(String),
+    ArityMismatch {
+        name: String,
+        expected: Arity,
+        found: usize,
+    },
+    ShadowsBuiltin(String),
+    RecursionLimit(usize),
+    /// Raised by a host function registered in a `FunctionRegistry`
+    Custom(String),
+}
+
+impl Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::UnboundVariable(name) => write!(f, "unbound variable '{name}'"),
+            EvalError::InvalidOperator(symbol) => write!(f, "invalid operator '{symbol}'"),
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::StraddlesZero { lo, hi } => write!(f, "divisor [{lo}, {hi}] straddles zero"),
+            EvalError::NotFinite => write!(f, "result is not a finite number"),
+            EvalError::NotInteger => write!(f, "expected an integer"),
+            EvalError::TooLarge => write!(f, "result is too large to compute"),
+            EvalError::DimensionMismatch { expected, found } => {
+                write!(f, "incompatible dimensions: expected {expected} but found {found}")
+            }
+            EvalError::UnknownUnit(name) => write!(f, "unknown unit '{name}'"),
+            EvalError::MissingRate(code) => write!(f, "no exchange rate for '{code}'"),
+            EvalError::UnitsUnsupported => write!(f, "units and dates need a number type with dimensions"),
+            EvalError::NotADate => write!(f, "expected a date"),
+            EvalError::NotReal => write!(f, "expected a real number, complex numbers need the complex mode"),
+            EvalError::UnknownFunction(name) => write!(f, "unknown function '{name}'"),
+            EvalError::UnknownOperator(symbol) => write!(f, "unknown operator '{symbol}'"),
+            EvalError::ArityMismatch {
+                name,
+                expected,
+                found,
+            } => write!(f, "'{name}' takes {expected} argument(s) but {found} were given"),
+            EvalError::ShadowsBuiltin(name) => write!(f, "cannot redefine built-in function '{name}'"),
+            EvalError::RecursionLimit(limit) => write!(f, "recursion deeper than {limit} calls"),
+            EvalError::Custom(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+impl Error for EvalError {}
+
+/// A `RateTable` that could not be read.
+#[derive(Debug)]
+pub enum RateError {
+    Io(io::Error),
+    /// A line that is neither `base CODE` nor `CODE RATE [DECIMALS]`, counting from 1
+    Syntax { line: usize, text: String },
+    /// No `base CODE` line before the first rate
+    MissingBase,
+    /// A rate of zero or less given for a currency
+    NotPositive(String),
+}
+
+impl From<io::Error> for RateError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl Display for RateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RateError::Io(err) => write!(f, "cannot read exchange rates: {err}"),
+            RateError::Syntax { line, text } => write!(f, "invalid exchange rate on line {line}: '{text}'"),
+            RateError::MissingBase => write!(f, "exchange rates need a 'base' currency"),
+            RateError::NotPositive(code) => write!(f, "the exchange rate for '{code}' must be positive"),
+        }
+    }
+}
+
+impl Error for RateError {}
+
 #[derive(Debug)]
 pub enum ParserError {
     AddExpr,
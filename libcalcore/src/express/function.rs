@@ -0,0 +1,172 @@
+use std::{collections::HashMap, fmt::Display, rc::Rc};
+
+use super::{
+    builtins,
+    error::EvalError,
+    number::{Number, Numeric},
+};
+
+/// How many arguments a function accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Arity {
+    pub min: usize,
+    /// `None` for variadic functions
+    pub max: Option<usize>,
+}
+
+impl Arity {
+    pub const fn exact(n: usize) -> Self {
+        Self { min: n, max: Some(n) }
+    }
+
+    pub const fn range(min: usize, max: usize) -> Self {
+        Self { min, max: Some(max) }
+    }
+
+    pub const fn at_least(min: usize) -> Self {
+        Self { min, max: None }
+    }
+
+    pub fn accepts(&self, count: usize) -> bool {
+        count >= self.min && self.max.is_none_or(|max| count <= max)
+    }
+}
+
+impl Display for Arity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "{max}"),
+            Some(max) => write!(f, "{} to {max}", self.min),
+            None => write!(f, "at least {}", self.min),
+        }
+    }
+}
+
+/// A function implemented by the host application.
+///
+/// The evaluator checks the argument count against [`Callable::arity`]
+/// before calling [`Callable::call`].
+pub trait Callable<N: Numeric = Number> {
+    fn name(&self) -> &str;
+
+    fn arity(&self) -> Arity;
+
+    /// A short description shown to users, e.g. `"sqrt(x): square root of x"`.
+    fn doc(&self) -> &str {
+        ""
+    }
+
+    fn call(&self, args: &[N]) -> Result<N, EvalError>;
+}
+
+/// A Rust closure registered through [`FunctionRegistry::register_numeric_fn`].
+pub struct NativeFunction<F> {
+    name: String,
+    arity: Arity,
+    doc: String,
+    func: F,
+}
+
+impl<F> NativeFunction<F> {
+    pub fn new(name: &str, arity: Arity, doc: &str, func: F) -> Self {
+        Self {
+            name: String::from(name),
+            arity,
+            doc: String::from(doc),
+            func,
+        }
+    }
+}
+
+impl<N: Numeric, F: Fn(&[N]) -> Result<N, EvalError>> Callable<N> for NativeFunction<F> {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn arity(&self) -> Arity {
+        self.arity
+    }
+
+    fn doc(&self) -> &str {
+        &self.doc
+    }
+
+    fn call(&self, args: &[N]) -> Result<N, EvalError> {
+        (self.func)(args)
+    }
+}
+
+/// The functions an expression can call, looked up by name.
+#[derive(Clone)]
+pub struct FunctionRegistry<N: Numeric = Number> {
+    functions: HashMap<String, Rc<dyn Callable<N>>>,
+}
+
+impl<N: Numeric> Default for FunctionRegistry<N> {
+    fn default() -> Self {
+        Self {
+            functions: HashMap::new(),
+        }
+    }
+}
+
+impl<N: Numeric> std::fmt::Debug for FunctionRegistry<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.functions.keys()).finish()
+    }
+}
+
+impl<N: Numeric> FunctionRegistry<N> {
+    /// A registry without any functions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry holding the standard library from [`builtins`].
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        builtins::register(&mut registry);
+        registry
+    }
+
+    /// Register `callable` under its name, returning the function it replaces.
+    pub fn register<C: Callable<N> + 'static>(&mut self, callable: C) -> Option<Rc<dyn Callable<N>>> {
+        self.functions.insert(String::from(callable.name()), Rc::new(callable))
+    }
+
+    /// Register a closure working on the numbers of the evaluation directly.
+    pub fn register_numeric_fn<F>(&mut self, name: &str, arity: Arity, doc: &str, func: F) -> Option<Rc<dyn Callable<N>>>
+    where
+        F: Fn(&[N]) -> Result<N, EvalError> + 'static,
+    {
+        self.register(NativeFunction::new(name, arity, doc, func))
+    }
+
+    /// Register a closure on `f64` that cannot fail. Its result takes the
+    /// representation of the first argument.
+    pub fn register_fn<F>(&mut self, name: &str, arity: Arity, doc: &str, func: F) -> Option<Rc<dyn Callable<N>>>
+    where
+        F: Fn(&[f64]) -> f64 + 'static,
+    {
+        self.register_numeric_fn(name, arity, doc, move |args: &[N]| {
+            let mode = args.first().map(N::mode).unwrap_or_default();
+            N::from_f64(func(&args.iter().map(N::to_f64).collect::<Vec<_>>()), mode)
+        })
+    }
+
+    pub fn unregister(&mut self, name: &str) -> Option<Rc<dyn Callable<N>>> {
+        self.functions.remove(name)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Rc<dyn Callable<N>>> {
+        self.functions.get(name).cloned()
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.functions.contains_key(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Callable<N>> {
+        self.functions.values().map(|callable| callable.as_ref())
+    }
+}
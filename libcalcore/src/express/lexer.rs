@@ -1,10 +1,28 @@
-use std::{iter::Peekable, str::Chars};
+use std::{collections::VecDeque, iter::Peekable, str::Chars};
 
-use super::error::{LexerError, CalError};
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+
+use super::{
+    calendar::{self, SECONDS_PER_DAY},
+    error::{CalError, LexerError, NumberError},
+    span::{Span, Spanned},
+};
 
 /// Some section about to the symbols:
 ///
 /// `OpenPh` => `"("`, `ClosePh` => `")"`, `Add` => `"+"`, `Subtract` => `"-"`, `Multiply` => `"*"`, `Divide` => `"/"`, `Exponential` => `"^"`
+///
+/// `Comma` => `","`, `Assign` => `"="`, `Semicolon` => `";"`, `Newline` => `"\n"`, `Let` => `"let"`
+///
+/// `Imaginary` => `"2i"`, `"0.5i"`, `PlusMinus` => `"±"`, `To` => `"to"`
+///
+/// `Date` => `"2026-10-18"`, `Time` => `"17:30"`, `"09:15:30"`
+///
+/// `Factorial` => `"!"`, `DoubleFactorial` => `"!!"`, `Operator` => any other symbol given to [`Lexer::with_symbols`]
+///
+/// Pasted math symbols read as their ASCII counterparts: `"×"` and `"·"` as `Multiply`, `"÷"` as `Division`,
+/// `"−"` as `Minus` and `"π"` as `Ident("pi")`, while `Root` => `"√"` and `Superscript` => `"²"`, `"⁻¹"`
 #[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     OpenPh,
@@ -15,12 +33,97 @@ pub enum Token {
     Division,
     Exponential,
     Percent,
-    Number(f64),
+    PlusMinus,
+    Factorial,
+    DoubleFactorial,
+    /// A symbol registered in an operator table, like `//` or `<<`
+    Operator(String),
+    /// The prefix square root `√`
+    Root,
+    /// A power written in superscript digits, like the `²` of `x²` or the `⁻¹` of `s⁻¹`
+    Superscript(i32),
+    /// Number literals are kept exact, each number mode converts them as needed.
+    /// Besides `12.5` they may have an exponent like `6.02e23`, a `0x`, `0o` or `0b`
+    /// prefix, and `_` between digits like `1_000_000`
+    Number(BigDecimal),
+    /// A number literal directly followed by an `i` that does not start a name, as in `2i` but
+    /// not `5in`. A bare `i` is an `Ident`, read as the imaginary unit when evaluating if unbound
+    Imaginary(BigDecimal),
+    /// A date as the seconds since 1970-01-01 at midnight
+    Date(i64),
+    /// A time of day or duration as seconds, any number of hours is allowed
+    Time(i64),
+    Ident(String),
+    Comma,
+    Assign,
+    Semicolon,
+    Newline,
+    Let,
+    /// Unit conversion, as in `5 ft to cm`
+    To,
     EOF,
 }
 
+impl Token {
+    /// The symbol an operator table knows this token by, like `"+"` for `Plus`.
+    ///
+    /// Names are symbols as well, so word operators like `mod` can be registered.
+    pub fn symbol(&self) -> Option<&str> {
+        let symbol = match self {
+            Token::Plus => "+",
+            Token::Minus => "-",
+            Token::Multiply => "*",
+            Token::Division => "/",
+            Token::Exponential => "^",
+            Token::Percent => "%",
+            Token::PlusMinus => "±",
+            Token::Root => "√",
+            Token::Factorial => "!",
+            Token::DoubleFactorial => "!!",
+            Token::Operator(symbol) | Token::Ident(symbol) => symbol,
+            _ => return None,
+        };
+        Some(symbol)
+    }
+}
+
 pub static NUMBER_CHARS: &str = "0123456789.";
 pub static OPERATOR_CHARS: &str = "()+-*/^% \n";
+pub static SUPERSCRIPT_DIGITS: &str = "⁰¹²³⁴⁵⁶⁷⁸⁹";
+
+fn is_ident_start(ch: char) -> bool {
+    ch.is_alphabetic() || ch == '_'
+}
+
+/// Whether `ch` on its own reads as a built-in token spelled `ch`, where `π` reads as `pi`.
+fn is_builtin_symbol(ch: char) -> bool {
+    "+-*/^%±√!".contains(ch) || (is_ident_start(ch) && ch != 'π')
+}
+
+fn is_ident_continue(ch: char) -> bool {
+    // superscripts are numeric, but `x²` is `x` squared
+    (ch.is_alphanumeric() && !SUPERSCRIPT_DIGITS.contains(ch)) || ch == '_'
+}
+
+/// Any Unicode white space, so expressions pasted with non-breaking or wide spaces still lex.
+pub fn is_space(ch: char) -> bool {
+    ch.is_whitespace()
+}
+
+/// Only ASCII spaces, tabs and line breaks.
+pub fn is_ascii_space(ch: char) -> bool {
+    matches!(ch, ' ' | '\t' | '\r' | '\n')
+}
+
+/// Whether every `_` in a literal has a digit of base `radix` on both sides, as in `1_000`.
+fn separators_between_digits(literal: &str, radix: u32) -> bool {
+    let chars: Vec<char> = literal.chars().collect();
+    let is_digit = |i: usize| chars.get(i).is_some_and(|ch| ch.is_digit(radix));
+    chars
+        .iter()
+        .enumerate()
+        .all(|(i, ch)| *ch != '_' || (i > 0 && is_digit(i - 1) && is_digit(i + 1)))
+}
 
 pub fn tokenize<T: FromIterator<Token>>(expr_str: &str) -> Result<T, CalError> {
     Ok(Lexer::from(expr_str).collect()?.into_iter().collect::<T>())
@@ -28,11 +131,69 @@ pub fn tokenize<T: FromIterator<Token>>(expr_str: &str) -> Result<T, CalError> {
 
 pub struct Lexer<I: Iterator<Item = char>> {
     src: Peekable<I>,
+    /// Byte offset of the next char in `src`
+    pos: usize,
+    /// Tokens already read while looking for a date that turned out to be a subtraction
+    pending: VecDeque<Spanned<Token>>,
+    /// Chars skipped between tokens
+    whitespace: fn(char) -> bool,
+    /// Whether `\n` separates statements instead of being skipped
+    statements: bool,
+    /// Operator symbols besides the built-in ones, read as [`Token::Operator`]
+    symbols: Vec<String>,
 }
 
 impl<I: Iterator<Item = char>> Lexer<I> {
     pub fn new(chars: I) -> Self {
-        Self { src: chars.peekable() }
+        Self {
+            src: chars.peekable(),
+            pos: 0,
+            pending: VecDeque::new(),
+            whitespace: is_space,
+            statements: false,
+            symbols: vec![],
+        }
+    }
+
+    /// Read the longest of `symbols` as a [`Token::Operator`], as in `7 // 2`.
+    ///
+    /// Symbols spelled like a built-in token, such as `+`, are still read as that token,
+    /// and names are read as [`Token::Ident`] like any other.
+    pub fn with_symbols<'a>(mut self, symbols: impl IntoIterator<Item = &'a str>) -> Self {
+        self.symbols = symbols
+            .into_iter()
+            .filter(|symbol| {
+                let first = symbol.chars().next();
+                first.is_some_and(|ch| !is_ident_start(ch) && !NUMBER_CHARS.contains(ch) && !ch.is_whitespace())
+            })
+            .map(String::from)
+            .collect();
+        self
+    }
+
+    /// Skip the chars `whitespace` accepts between tokens instead of [`is_space`].
+    pub fn with_whitespace(mut self, whitespace: fn(char) -> bool) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+
+    /// In statement mode a newline is read as [`Token::Newline`], otherwise it is whitespace.
+    pub fn set_statements(&mut self, statements: bool) {
+        self.statements = statements;
+    }
+
+    pub fn statements(&self) -> bool {
+        self.statements
+    }
+
+    fn is_whitespace(&self, ch: char) -> bool {
+        !(self.statements && ch == '\n') && (self.whitespace)(ch)
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let ch = self.src.next()?;
+        self.pos += ch.len_utf8();
+        Some(ch)
     }
 
     // pub fn new(src: &'a str) -> Self {
@@ -41,27 +202,73 @@ impl<I: Iterator<Item = char>> Lexer<I> {
     //     }
     // }
 
-    pub fn read(&mut self) -> Result<Token, CalError> {
-        if let Some(ch) = self.src.next() {
+    /// Hand out `token` again before reading on, as if it had not been read yet.
+    pub fn unread(&mut self, token: Spanned<Token>) {
+        self.pending.push_front(token);
+    }
+
+    /// Read the next token together with the span of source it covers.
+    pub fn read(&mut self) -> Result<Spanned<Token>, CalError> {
+        if let Some(token) = self.pending.pop_front() {
+            return Ok(token);
+        }
+        while let Some(&ch) = self.src.peek() {
+            if !self.is_whitespace(ch) {
+                break;
+            }
+            self.next_char();
+        }
+        let start = self.pos;
+        let token = self.read_token(start)?;
+        // a failed date leaves the rest of the subtraction pending right after this token
+        let end = self.pending.front().map_or(self.pos, |token| token.span.start);
+        Ok(Spanned::new(token, Span::new(start, end)))
+    }
+
+    fn read_token(&mut self, start: usize) -> Result<Token, CalError> {
+        if let Some(ch) = self.next_char() {
+            if self.symbols.iter().any(|symbol| symbol.starts_with(ch)) {
+                if let Some(token) = self.read_symbol(ch, start)? {
+                    return Ok(token);
+                }
+            }
             match ch {
                 '(' => Ok(Token::OpenPh),
                 ')' => Ok(Token::ClosePh),
                 '+' => Ok(Token::Plus),
-                '-' => Ok(Token::Minus),
-                '*' => Ok(Token::Multiply),
-                '/' => Ok(Token::Division),
+                '-' | '−' => Ok(Token::Minus),
+                '*' | '×' | '·' | '⋅' => Ok(Token::Multiply),
+                '/' | '÷' => Ok(Token::Division),
                 '^' => Ok(Token::Exponential),
                 '%' => Ok(Token::Percent),
+                '!' if self.src.peek() == Some(&'!') => {
+                    self.next_char();
+                    Ok(Token::DoubleFactorial)
+                }
+                '!' => Ok(Token::Factorial),
+                '±' => Ok(Token::PlusMinus),
+                '√' => Ok(Token::Root),
+                // on its own, so `πr` is `π` followed by `r`
+                'π' => Ok(Token::Ident(String::from("pi"))),
+                '⁻' | '⁺' => self.read_superscript(ch, start),
+                _ if SUPERSCRIPT_DIGITS.contains(ch) => self.read_superscript(ch, start),
+                ',' => Ok(Token::Comma),
+                '=' => Ok(Token::Assign),
+                ';' => Ok(Token::Semicolon),
+                '\n' => Ok(Token::Newline),
+                '0' if matches!(self.src.peek(), Some('x' | 'X' | 'o' | 'O' | 'b' | 'B')) => self.read_radix(start),
                 _ if NUMBER_CHARS.contains(ch) => {
-                    let mut buf = String::from(ch);
-                    while let Some(ch) = self.src.peek().filter(|ch| NUMBER_CHARS.contains(**ch)) {
-                        buf.push(*ch);
-                        self.src.next();
+                    let buf = self.read_digits(ch);
+                    let is_integer = buf.bytes().all(|b| b.is_ascii_digit());
+                    match self.src.peek() {
+                        Some(':') if is_integer => self.read_time(buf, start),
+                        Some('-') if is_integer && buf.len() == 4 => self.read_date(buf, start),
+                        _ => self.number(buf, start),
                     }
-                    Ok(Token::Number(buf.parse::<f64>().map_err(|_| CalError::LexError(LexerError::InvalidNumber(buf)))?))
                 }
+                _ if is_ident_start(ch) => Ok(self.read_ident(ch)),
                 _ => {
-                    CalError::lex(LexerError::UnknowChar(ch))
+                    CalError::lex(LexerError::UnknowChar(ch), Span::new(start, self.pos))
                 }
             }
         } else {
@@ -69,11 +276,253 @@ impl<I: Iterator<Item = char>> Lexer<I> {
         }
     }
 
-    pub fn collect(mut self) -> Result<Vec<Token>, CalError> {
+    /// The longest registered symbol starting with `first`, or `None` to read `first` as usual.
+    fn read_symbol(&mut self, first: char, start: usize) -> Result<Option<Token>, CalError> {
+        let mut buf = String::from(first);
+        while let Some(&ch) = self.src.peek() {
+            let longer = format!("{buf}{ch}");
+            if !self.symbols.iter().any(|symbol| symbol.starts_with(&longer)) {
+                break;
+            }
+            buf = longer;
+            self.next_char();
+        }
+        if buf.len() == first.len_utf8() {
+            // a built-in symbol wins over registering it again
+            let registered = self.symbols.contains(&buf) && !is_builtin_symbol(first);
+            return Ok(registered.then_some(Token::Operator(buf)));
+        }
+        // what the chars read so far are without the registered symbols
+        let mut tokens = match Lexer::new(buf.chars()).collect_spanned() {
+            Ok(tokens) => tokens,
+            Err(_) if self.symbols.contains(&buf) => return Ok(Some(Token::Operator(buf))),
+            Err(CalError::LexError(le, span)) => {
+                return CalError::lex(le, Span::new(start + span.start, start + span.end))
+            }
+            Err(err) => return Err(err),
+        };
+        let builtin = tokens.len() == 1 && tokens[0].node.symbol() == Some(buf.as_str());
+        if builtin || !self.symbols.contains(&buf) {
+            // a built-in symbol, or the start of a longer symbol like the `*=` of `*=*` read as `*` and `=`
+            for token in &mut tokens {
+                token.span = Span::new(start + token.span.start, start + token.span.end);
+            }
+            let first = tokens.remove(0);
+            self.pending.extend(tokens);
+            return Ok(Some(first.node));
+        }
+        Ok(Some(Token::Operator(buf)))
+    }
+
+    fn read_ident(&mut self, first: char) -> Token {
+        let mut buf = String::from(first);
+        while let Some(&ch) = self.src.peek().filter(|ch| is_ident_continue(**ch)) {
+            buf.push(ch);
+            self.next_char();
+        }
+        match buf.as_str() {
+            "let" => Token::Let,
+            "to" => Token::To,
+            _ => Token::Ident(buf),
+        }
+    }
+
+    /// Superscript digits with an optional leading `⁻` or `⁺`.
+    fn read_superscript(&mut self, first: char, start: usize) -> Result<Token, CalError> {
+        let mut buf = String::from(first);
+        let mut exponent = String::from(match first {
+            '⁻' => "-",
+            '⁺' => "+",
+            _ => "",
+        });
+        while let Some(&ch) = self.src.peek().filter(|ch| SUPERSCRIPT_DIGITS.contains(**ch)) {
+            buf.push(ch);
+            self.next_char();
+        }
+        for ch in buf.chars() {
+            if let Some(digit) = SUPERSCRIPT_DIGITS.chars().position(|sup| sup == ch) {
+                exponent.push(char::from(b'0' + digit as u8));
+            }
+        }
+        match exponent.parse() {
+            Ok(exponent) => Ok(Token::Superscript(exponent)),
+            Err(_) => CalError::lex(
+                LexerError::InvalidNumber(buf, NumberError::NoDigits),
+                Span::new(start, self.pos),
+            ),
+        }
+    }
+
+    /// Digits, `.` and `_` separators.
+    fn read_digits(&mut self, first: char) -> String {
+        let mut buf = String::from(first);
+        while let Some(&ch) = self.src.peek().filter(|ch| NUMBER_CHARS.contains(**ch) || **ch == '_') {
+            buf.push(ch);
+            self.next_char();
+        }
+        buf
+    }
+
+    /// The decimal literal starting with `buf`, with an exponent if one follows and
+    /// imaginary if an `i` does. An `e` without exponent digits starts the next token
+    /// instead, so `2em` is `2` in the unit `em`.
+    fn number(&mut self, mut buf: String, start: usize) -> Result<Token, CalError> {
+        if let Some(e) = self.src.next_if(|ch| matches!(ch, 'e' | 'E')) {
+            let e_start = self.pos;
+            self.pos += 1;
+            buf.push(e);
+            if let Some(sign) = self.src.next_if(|ch| matches!(ch, '+' | '-')) {
+                self.pos += 1;
+                buf.push(sign);
+            } else if !self.src.peek().is_some_and(char::is_ascii_digit) {
+                let ident = self.read_ident(e);
+                self.pending.push_back(Spanned::new(ident, Span::new(e_start, self.pos)));
+                buf.pop();
+                return self.decimal(buf, start, e_start);
+            }
+            let exp_start = buf.len();
+            while let Some(ch) = self.src.next_if(|ch| ch.is_ascii_digit() || *ch == '_') {
+                self.pos += 1;
+                buf.push(ch);
+            }
+            if buf.len() == exp_start {
+                let error = LexerError::InvalidNumber(buf, NumberError::MissingExponent);
+                return CalError::lex(error, Span::new(start, self.pos));
+            }
+        }
+        self.decimal(buf, start, self.pos)
+    }
+
+    /// The decimal literal `buf` spanning `start..end`.
+    fn decimal(&mut self, buf: String, start: usize, end: usize) -> Result<Token, CalError> {
+        let invalid = |error| CalError::lex(LexerError::InvalidNumber(buf.clone(), error), Span::new(start, end));
+        if !separators_between_digits(&buf, 10) {
+            return invalid(NumberError::Separator);
+        }
+        if buf.matches('.').count() > 1 {
+            return invalid(NumberError::ExtraPoint);
+        }
+        let num = match buf.replace('_', "").parse::<BigDecimal>() {
+            Ok(num) => num,
+            Err(_) => return invalid(NumberError::NoDigits),
+        };
+        if end == self.pos && self.src.peek() == Some(&'i') {
+            let i_start = self.pos;
+            self.next_char();
+            // `5in` is five inches, only an `i` on its own makes the number imaginary
+            if !self.src.peek().is_some_and(|ch| is_ident_continue(*ch)) {
+                return Ok(Token::Imaginary(num));
+            }
+            let ident = self.read_ident('i');
+            self.pending.push_back(Spanned::new(ident, Span::new(i_start, self.pos)));
+        }
+        Ok(Token::Number(num))
+    }
+
+    /// `0x`, `0o` or `0b` and the digits in that base, after the leading `0` was read.
+    fn read_radix(&mut self, start: usize) -> Result<Token, CalError> {
+        let prefix = self.next_char().unwrap_or_default();
+        let radix = match prefix.to_ascii_lowercase() {
+            'x' => 16,
+            'o' => 8,
+            _ => 2,
+        };
+        let mut buf = format!("0{prefix}");
+        while let Some(&ch) = self.src.peek().filter(|ch| is_ident_continue(**ch)) {
+            buf.push(ch);
+            self.next_char();
+        }
+        let digits = &buf[2..];
+        let error = if digits.is_empty() {
+            Some(NumberError::NoDigits)
+        } else if let Some(digit) = digits.chars().find(|ch| *ch != '_' && !ch.is_digit(radix)) {
+            Some(NumberError::InvalidDigit { digit, radix })
+        } else if !separators_between_digits(digits, radix) {
+            Some(NumberError::Separator)
+        } else {
+            None
+        };
+        if let Some(error) = error {
+            return CalError::lex(LexerError::InvalidNumber(buf, error), Span::new(start, self.pos));
+        }
+        let value = BigInt::parse_bytes(digits.replace('_', "").as_bytes(), radix).unwrap_or_default();
+        Ok(Token::Number(BigDecimal::from(value)))
+    }
+
+    /// `HH:MM` or `HH:MM:SS`, starting with the `hours` already read.
+    fn read_time(&mut self, hours: String, start: usize) -> Result<Token, CalError> {
+        let mut text = hours.clone();
+        let mut seconds = hours.parse::<i64>().ok().and_then(|hours| hours.checked_mul(3600));
+        for scale in [60, 1] {
+            if self.src.next_if_eq(&':').is_none() {
+                break;
+            }
+            self.pos += 1;
+            text.push(':');
+            let mut field = String::new();
+            while let Some(ch) = self.src.next_if(char::is_ascii_digit) {
+                self.pos += 1;
+                field.push(ch);
+            }
+            text.push_str(&field);
+            let value = field.parse::<i64>().ok().filter(|value| field.len() == 2 && *value < 60);
+            seconds = seconds.zip(value).and_then(|(seconds, value)| seconds.checked_add(value * scale));
+        }
+        match seconds {
+            Some(seconds) => Ok(Token::Time(seconds)),
+            None => CalError::lex(LexerError::InvalidDate(text), Span::new(start, self.pos)),
+        }
+    }
+
+    /// `YYYY-MM-DD` after the year in `buf`. Anything else is a subtraction, so
+    /// `2026-10` is the number `2026` with `-` and `10` left pending.
+    fn read_date(&mut self, year: String, start: usize) -> Result<Token, CalError> {
+        let mut fields = vec![];
+        while fields.len() < 2 && self.src.peek() == Some(&'-') {
+            let minus = self.pos;
+            self.next_char();
+            self.pending.push_back(Spanned::new(Token::Minus, Span::new(minus, self.pos)));
+            let Some(ch) = self.src.next_if(|ch| NUMBER_CHARS.contains(*ch)) else {
+                break;
+            };
+            let field_start = self.pos;
+            self.pos += 1;
+            let field = self.read_digits(ch);
+            // the number may leave a unit after it pending, which has to come after the number
+            let index = self.pending.len();
+            let token = self.number(field.clone(), field_start)?;
+            let end = self.pending.get(index).map_or(self.pos, |token| token.span.start);
+            // nothing read past the two digits, like the exponent of `2026-10-18e5`
+            let is_field = field.len() == 2 && field.bytes().all(|b| b.is_ascii_digit()) && end == self.pos;
+            let is_field = is_field && end == field_start + field.len();
+            let is_field = is_field && matches!(token, Token::Number(_));
+            self.pending.insert(index, Spanned::new(token, Span::new(field_start, end)));
+            if !is_field {
+                break;
+            }
+            fields.push(field);
+        }
+        let [month, day] = &fields[..] else {
+            return Ok(Token::Number(year.parse().unwrap()));
+        };
+        self.pending.clear();
+        let text = format!("{year}-{month}-{day}");
+        let days = calendar::days_from_civil(year.parse().unwrap(), month.parse().unwrap(), day.parse().unwrap());
+        match days {
+            Some(days) => Ok(Token::Date(days * SECONDS_PER_DAY)),
+            None => CalError::lex(LexerError::InvalidDate(text), Span::new(start, self.pos)),
+        }
+    }
+
+    pub fn collect(self) -> Result<Vec<Token>, CalError> {
+        Ok(self.collect_spanned()?.into_iter().map(|tok| tok.node).collect())
+    }
+
+    pub fn collect_spanned(mut self) -> Result<Vec<Spanned<Token>>, CalError> {
         let mut tokens = vec![];
         loop {
             let token = self.read()?;
-            if let Token::EOF = token {
+            if let Token::EOF = token.node {
                 break;
             }
             tokens.push(token);
@@ -86,4 +535,4 @@ impl<'a> From<&'a str> for Lexer<Chars<'a>> {
     fn from(value: &'a str) -> Self {
         Self::new(value.chars())
     }
-}
\ No newline at end of file
+}
@@ -1,11 +1,21 @@
+pub mod builtins;
+pub mod calendar;
+pub mod currency;
+pub mod env;
 pub mod error;
+pub mod function;
 pub mod lexer;
+pub mod number;
+pub mod operator;
 pub mod parser;
+pub mod span;
+pub mod unit;
 
 #[cfg(test)]
 pub mod tests {
 
     use super::lexer::*;
+    use super::number::Number;
     use super::parser::*;
 
     #[test]
@@ -58,4 +68,1098 @@ pub mod tests {
 
         assert!(f64::abs(ast.eval().unwrap() - (1.0 + 2.0 / 3.0 * 4.0 + f64::powf(1.0 % 4.0, 1.2))) < f64::EPSILON)
     }
+
+    #[test]
+    fn test_span() {
+        use super::error::CalError;
+        use super::span::Span;
+
+        let tokens = Lexer::from("12+(3)")
+            .collect_spanned()
+            .unwrap()
+            .into_iter()
+            .map(|tok| tok.span)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec![Span::new(0, 2), Span::new(2, 3), Span::new(3, 4), Span::new(4, 5), Span::new(5, 6)]
+        );
+
+        let express = "1+2.2.2";
+        let err = tokenize::<Vec<Token>>(express).unwrap_err();
+        assert!(matches!(err, CalError::LexError(_, _)));
+        assert_eq!(err.span(), Span::new(2, 7));
+        assert!(err.render(express).ends_with("1+2.2.2\n  ^^^^^"));
+
+        use lookahead::LookAhead;
+        let express = "1+2*(3-4";
+        let err = LookAhead::try_from(express).unwrap().parse_expr().unwrap_err();
+        assert_eq!(err.span(), Span::new(8, 8));
+        assert!(err.render(express).ends_with("1+2*(3-4\n        ^"));
+
+        let ast = LookAhead::try_from("10-2*3").unwrap().parse_expr().unwrap();
+        assert_eq!(ast.span(), Span::new(0, 6));
+    }
+
+    #[test]
+    fn test_variables() {
+        use super::env::Environment;
+        use super::error::{CalError, EvalError};
+        use lookahead::LookAhead;
+
+        let mut env = Environment::new();
+        env.set("x", 3.0);
+
+        let ast = LookAhead::try_from("x^2+1").unwrap().parse_expr().unwrap();
+        assert_eq!(ast.eval_with(&env).unwrap(), Number::from(10.0));
+
+        let ast = LookAhead::try_from("2*pi").unwrap().parse_expr().unwrap();
+        assert_eq!(ast.eval().unwrap(), 2.0 * std::f64::consts::PI);
+
+        let ast = LookAhead::try_from("x+y_1").unwrap().parse_expr().unwrap();
+        let err = ast.eval_with(&env).unwrap_err();
+        assert!(matches!(&err, CalError::EvalError(EvalError::UnboundVariable(name), _) if name == "y_1"));
+        assert_eq!(err.span(), super::span::Span::new(2, 5));
+    }
+
+    #[test]
+    fn test_program() {
+        use super::env::Environment;
+        use lookahead::LookAhead;
+
+        let mut env = Environment::new();
+        let program = LookAhead::try_from("let r = 2; pi * r^2").unwrap().parse_program().unwrap();
+        assert_eq!(program.stmts().len(), 2);
+        assert_eq!(program.eval_with(&mut env).unwrap(), Some(Number::from(std::f64::consts::PI * 4.0)));
+        assert_eq!(env.get("r"), Some(&Number::from(2.0)));
+
+        // the environment persists between programs
+        let program = LookAhead::try_from("a=r+1\n\nb=a*a\nb-a;").unwrap().parse_program().unwrap();
+        assert_eq!(program.eval_with(&mut env).unwrap(), Some(Number::from(6.0)));
+
+        let program = LookAhead::try_from(";\n").unwrap().parse_program().unwrap();
+        assert_eq!(program.eval_with(&mut env).unwrap(), None);
+
+        assert!(LookAhead::try_from("1+2=3").unwrap().parse_program().is_err());
+        assert!(LookAhead::try_from("let 1 = 2").unwrap().parse_program().is_err());
+        assert!(LookAhead::try_from("a = 1 2").unwrap().parse_program().is_err());
+    }
+
+    #[test]
+    fn test_builtin_calls() {
+        use super::error::{CalError, EvalError};
+        use lookahead::LookAhead;
+
+        let eval = |express: &str| LookAhead::try_from(express).unwrap().parse_expr().unwrap().eval();
+
+        assert_eq!(eval("sqrt(16)+abs(-2)").unwrap(), 6.0);
+        assert_eq!(eval("max(1, 5, 3) - min(4, 2)").unwrap(), 3.0);
+        assert_eq!(eval("floor(2.7)+round(2.5)+ceil(0.1)").unwrap(), 6.0);
+        assert_eq!(eval("log(1000)").unwrap(), 3.0);
+        assert_eq!(eval("log(8, 2)").unwrap(), 3.0);
+        assert_eq!(eval("ln(e)").unwrap(), 1.0);
+        assert!((eval("sin(pi/2)+cos(0)").unwrap() - 2.0).abs() < f64::EPSILON);
+
+        assert!(matches!(
+            eval("sqrt(1, 2)"),
+            Err(CalError::EvalError(EvalError::ArityMismatch { found: 2, .. }, _))
+        ));
+        assert!(matches!(eval("max()"), Err(CalError::EvalError(EvalError::ArityMismatch { found: 0, .. }, _))));
+        assert!(matches!(eval("foo(1)"), Err(CalError::EvalError(EvalError::UnknownFunction(_), _))));
+        assert!(LookAhead::try_from("max(1,").unwrap().parse_expr().is_err());
+    }
+
+    #[test]
+    fn test_user_functions() {
+        use super::env::Environment;
+        use super::error::{CalError, EvalError};
+        use lookahead::LookAhead;
+
+        let mut env = Environment::new();
+        let mut run = |src: &str| LookAhead::try_from(src).unwrap().parse_program().unwrap().eval_with(&mut env);
+
+        assert_eq!(run("f(x, y) = x^2 + y^2").unwrap(), None);
+        assert_eq!(run("f(3, 4)").unwrap(), Some(Number::from(25.0)));
+        assert_eq!(run("let area(r) = pi * r^2; area(1)").unwrap(), Some(Number::from(std::f64::consts::PI)));
+
+        // parameters are only visible inside the body they belong to
+        assert_eq!(run("x = 10; g(y) = x + y; h(x) = g(1); h(100)").unwrap(), Some(Number::from(11.0)));
+        assert!(matches!(
+            run("k(a) = b; j(b) = k(1); j(2)"),
+            Err(CalError::EvalError(EvalError::UnboundVariable(_), _))
+        ));
+
+        assert!(matches!(
+            run("f(1)"),
+            Err(CalError::EvalError(EvalError::ArityMismatch { found: 1, .. }, _))
+        ));
+        assert!(matches!(
+            run("loop(n) = loop(n + 1); loop(0)"),
+            Err(CalError::EvalError(EvalError::RecursionLimit(_), _))
+        ));
+        assert!(matches!(
+            run("sqrt(x) = x"),
+            Err(CalError::EvalError(EvalError::ShadowsBuiltin(_), _))
+        ));
+
+        assert!(LookAhead::try_from("f(x, x) = x").unwrap().parse_program().is_err());
+        assert!(LookAhead::try_from("f(1) = 2").unwrap().parse_program().is_err());
+    }
+
+    #[test]
+    fn test_function_registry() {
+        use super::env::Environment;
+        use super::error::{CalError, EvalError};
+        use super::function::{Arity, Callable, FunctionRegistry};
+        use super::number::Numeric;
+        use lookahead::LookAhead;
+
+        struct Margin;
+
+        impl Callable for Margin {
+            fn name(&self) -> &str {
+                "margin"
+            }
+
+            fn arity(&self) -> Arity {
+                Arity::exact(2)
+            }
+
+            fn doc(&self) -> &str {
+                "margin(price, cost): profit as a fraction of price"
+            }
+
+            fn call(&self, args: &[Number]) -> Result<Number, EvalError> {
+                let (price, cost) = (&args[0], &args[1]);
+                if price.to_f64() == 0.0 {
+                    return Err(EvalError::Custom(String::from("price must not be zero")));
+                }
+                price.sub(cost)?.div(price)
+            }
+        }
+
+        let mut env = Environment::new();
+        env.registry_mut().register_fn("tax", Arity::exact(1), "tax(x): 20% of x", |args| args[0] * 0.2);
+        env.register(Margin);
+        let mut run = |src: &str| LookAhead::try_from(src).unwrap().parse_program().unwrap().eval_with(&mut env);
+
+        assert_eq!(run("tax(50) + margin(200, 150)").unwrap(), Some(Number::from(10.25)));
+        assert!(matches!(run("margin(0, 1)"), Err(CalError::EvalError(EvalError::Custom(_), _))));
+        assert!(matches!(run("tax(1, 2)"), Err(CalError::EvalError(EvalError::ArityMismatch { .. }, _))));
+        assert!(matches!(run("tax(x) = x"), Err(CalError::EvalError(EvalError::ShadowsBuiltin(_), _))));
+
+        // a host may also start from an empty registry
+        let mut env = Environment::<Number>::with_registry(FunctionRegistry::new());
+        let program = LookAhead::try_from("sqrt(4)").unwrap().parse_program().unwrap();
+        assert!(matches!(
+            program.eval_with(&mut env),
+            Err(CalError::EvalError(EvalError::UnknownFunction(_), _))
+        ));
+        assert!(Environment::new().registry().iter().all(|callable| !callable.doc().is_empty()));
+    }
+
+    #[test]
+    fn test_decimal_mode() {
+        use super::env::Environment;
+        use super::error::{CalError, EvalError};
+        use super::number::NumberMode;
+        use lookahead::LookAhead;
+
+        let mut env = Environment::new();
+        env.set_mode(NumberMode::decimal());
+        let mut run = |src: &str| {
+            LookAhead::try_from(src)
+                .unwrap()
+                .parse_program()
+                .unwrap()
+                .eval_with(&mut env)
+                .map(|num| num.unwrap().to_string())
+        };
+
+        assert_eq!(run("0.1+0.2").unwrap(), "0.3");
+        assert_eq!(run("1/3").unwrap(), format!("0.{}", "3".repeat(50)));
+        assert_eq!(run("2^-2 + 1.5%1").unwrap(), "0.75");
+        assert_eq!(run("x = 0.1; x*3").unwrap(), "0.3");
+        assert_eq!(run("sqrt(16) + .5").unwrap(), "4.5");
+        assert!(matches!(run("1/(1-1)"), Err(CalError::EvalError(EvalError::DivisionByZero, _))));
+        assert!(matches!(run("(0-8)^0.5"), Err(CalError::EvalError(EvalError::NotFinite, _))));
+
+        env.set_mode(NumberMode::Decimal { precision: 5 });
+        let program = LookAhead::try_from("2/3").unwrap().parse_program().unwrap();
+        assert_eq!(program.eval_with(&mut env).unwrap().unwrap().to_string(), "0.66667");
+
+        // the float mode keeps its binary artefacts
+        assert_ne!(LookAhead::try_from("0.1+0.2").unwrap().parse_expr().unwrap().eval().unwrap(), 0.3);
+    }
+
+    #[test]
+    fn test_rational_mode() {
+        use super::env::Environment;
+        use super::number::{FractionStyle, NumberFormat, NumberMode, Numeric};
+        use lookahead::LookAhead;
+
+        let mut env = Environment::new();
+        env.set_mode(NumberMode::Rational);
+        let mut run = |src: &str| {
+            LookAhead::try_from(src)
+                .unwrap()
+                .parse_program()
+                .unwrap()
+                .eval_with(&mut env)
+                .unwrap()
+                .unwrap()
+        };
+
+        assert_eq!(run("1/3*3").to_string(), "1");
+        assert_eq!(run("0.1+0.2").to_string(), "3/10");
+        assert_eq!(run("7/3").to_string(), "7/3");
+        assert_eq!(run("(9/4)^0.5 + 2^-1").to_string(), "2");
+        assert_eq!(run("2^100").to_string(), "1267650600228229401496703205376");
+        assert_eq!(run("7/3 % 1").to_string(), "1/3");
+
+        // irrational results fall back to floating point
+        assert!(matches!(run("2^0.5"), Number::Float(_)));
+        assert!(matches!(run("sqrt(2)"), Number::Float(_)));
+        assert!(matches!(run("sqrt(4)"), Number::Rational(_)));
+
+        let mixed = NumberFormat {
+            fraction: FractionStyle::Mixed,
+            ..Default::default()
+        };
+        assert_eq!(run("7/3").format(&mixed), "2 1/3");
+        assert_eq!(run("-7/3").format(&mixed), "-2 1/3");
+        assert_eq!(run("2/3").format(&mixed), "2/3");
+        assert_eq!(run("6/3").format(&mixed), "2");
+    }
+
+    #[test]
+    fn test_numeric_backends() {
+        use super::env::Environment;
+        use super::number::{Decimal, NumberMode, Numeric, Precision, Rational};
+        use lookahead::LookAhead;
+
+        let expr = LookAhead::try_from("1/3 + 1/6 + max(abs(-2), sqrt(4))").unwrap().parse_expr().unwrap();
+
+        let float = expr.eval_with(&Environment::<f64>::default()).unwrap();
+        assert!((float - 2.5).abs() < 1e-12);
+
+        let mut env = Environment::<Decimal>::default();
+        env.set_mode(Precision(10));
+        assert_eq!(expr.eval_with(&env).unwrap().to_string(), "2.5");
+
+        let rational = expr.eval_with(&Environment::<Rational>::default()).unwrap();
+        assert_eq!(rational.to_string(), "5/2");
+
+        // constants are read at the precision of the current mode
+        let pi = LookAhead::try_from("pi").unwrap().parse_expr().unwrap();
+        let mut env = Environment::<Decimal>::default();
+        env.set_mode(Precision(30));
+        assert_eq!(pi.eval_with(&env).unwrap().to_string(), "3.14159265358979323846264338328");
+        assert_eq!(pi.eval_with(&Environment::<f64>::default()).unwrap(), std::f64::consts::PI);
+
+        let mut env = Environment::new();
+        assert_eq!(pi.eval_with(&env).unwrap().to_f64(), std::f64::consts::PI);
+        env.set_mode(NumberMode::decimal());
+        let digits = "3.1415926535897932384626433832795028841971693993751";
+        assert_eq!(pi.eval_with(&env).unwrap().to_string(), digits);
+        let two_pi = LookAhead::try_from("2pi").unwrap().parse_expr().unwrap();
+        assert!(two_pi.eval_with(&env).unwrap().to_string().starts_with("6.28318530717958647692528676655"));
+        env.set("pi", Number::from(3.0));
+        assert_eq!(pi.eval_with(&env).unwrap().to_string(), "3");
+    }
+
+    #[test]
+    fn test_complex_mode() {
+        use super::env::Environment;
+        use super::error::{CalError, EvalError};
+        use super::number::{Complex, ComplexStyle, NumberFormat, NumberMode, Numeric};
+        use lookahead::LookAhead;
+
+        assert_eq!(
+            Lexer::from("2i + i").collect().unwrap(),
+            vec![
+                Token::Imaginary(2.into()),
+                Token::Plus,
+                Token::Ident(String::from("i")),
+            ]
+        );
+        assert_eq!(
+            Lexer::from("5in 2i2").collect().unwrap(),
+            vec![
+                Token::Number(5.into()),
+                Token::Ident(String::from("in")),
+                Token::Number(2.into()),
+                Token::Ident(String::from("i2")),
+            ]
+        );
+
+        let mut env = Environment::new();
+        env.set_mode(NumberMode::Complex);
+        let mut run = |src: &str| LookAhead::try_from(src).unwrap().parse_program().unwrap().eval_with(&mut env);
+        let value = |src: &str, run: &mut dyn FnMut(&str) -> _| match run(src) {
+            Ok(Some(Number::Complex(c))) => c,
+            other => panic!("{src} gave {other:?}"),
+        };
+
+        assert_eq!(value("sqrt(-1)", &mut run), Complex::new(0.0, 1.0));
+        assert_eq!(value("(1+2i)*(3-i)", &mut run), Complex::new(5.0, 5.0));
+        assert_eq!(value("i^2", &mut run), Complex::new(-1.0, 0.0));
+        assert_eq!(value("z = 1 - i; z * z", &mut run).to_string(), "-2i");
+        assert_eq!(value("abs(3+4i)", &mut run).to_string(), "5");
+        let euler = value("e^(i*pi)", &mut run);
+        assert!((euler.re() + 1.0).abs() < 1e-12 && euler.im().abs() < 1e-12);
+
+        let polar = NumberFormat {
+            complex: ComplexStyle::Polar,
+            ..Default::default()
+        };
+        let z = run("2i").unwrap().unwrap();
+        assert_eq!(z.format(&polar), format!("2*e^({}i)", std::f64::consts::FRAC_PI_2));
+        assert_eq!(z.format(&NumberFormat::default()), "2i");
+        assert!(matches!(run("5i % 2"), Err(CalError::EvalError(EvalError::NotReal, _))));
+
+        // a bound `i` is not the imaginary unit
+        assert_eq!(value("i = 2; i * i", &mut run).to_string(), "4");
+
+        // outside the complex mode imaginary numbers are rejected and `i` is a name like any other
+        let expr = LookAhead::try_from("1 + 2i").unwrap().parse_expr().unwrap();
+        assert!(matches!(expr.eval(), Err(CalError::EvalError(EvalError::NotReal, _))));
+        let expr = LookAhead::try_from("1 + i").unwrap().parse_expr().unwrap();
+        assert!(matches!(expr.eval(), Err(CalError::EvalError(EvalError::UnboundVariable(_), _))));
+        let mut env = Environment::new();
+        let mut run = |src: &str| LookAhead::try_from(src).unwrap().parse_program().unwrap().eval_with(&mut env);
+        assert_eq!(run("i = 3; i + 1").unwrap(), Some(Number::from(4.0)));
+        assert_eq!(run("f(i) = i*2; f(5)").unwrap(), Some(Number::from(10.0)));
+    }
+
+    #[test]
+    fn test_interval_mode() {
+        use super::env::Environment;
+        use super::error::{CalError, EvalError};
+        use super::number::{Interval, NumberMode};
+        use lookahead::LookAhead;
+
+        let env = Environment::<Interval>::default();
+        let run = |src: &str| LookAhead::try_from(src).unwrap().parse_expr().unwrap().eval_with(&env);
+        let encloses = |src: &str, lo: f64, hi: f64| {
+            let value = run(src).unwrap();
+            assert!(value.lo() <= lo && hi <= value.hi(), "{src} gave {value}");
+            // outward rounding only adds a few ulps
+            assert!(value.width() - (hi - lo) < 1e-12, "{src} gave {value}");
+        };
+
+        encloses("3.2±0.1", 3.1, 3.3);
+        encloses("2*3±1", 4.0, 8.0);
+        encloses("(2±0.5)*(3±1)", 3.0, 10.0);
+        encloses("(1±0.5)^2 - 1", -0.75, 1.25);
+        encloses("(-1±2)^2", 0.0, 9.0);
+        encloses("0.1+0.2", 0.3, 0.3);
+        encloses("sqrt(4±0)", 2.0, 2.0);
+        encloses("sin(0±0.1)", -0.1f64.sin(), 0.1f64.sin());
+        // 0.1 has no exact f64, so its literal spans the two neighbours
+        let tenth = run("0.1").unwrap();
+        assert_eq!(tenth.lo().next_up(), tenth.hi());
+        assert!(matches!(
+            run("1/(0.5±1)"),
+            Err(CalError::EvalError(EvalError::StraddlesZero { .. }, _))
+        ));
+
+        // ± needs the interval mode when the number type is chosen at runtime
+        let mut env = Environment::new();
+        let mut run = |src: &str| LookAhead::try_from(src).unwrap().parse_program().unwrap().eval_with(&mut env);
+        assert!(matches!(
+            run("3±1"),
+            Err(CalError::EvalError(EvalError::InvalidOperator(symbol), _)) if symbol == "±"
+        ));
+        run("x = 2").unwrap();
+        env.set_mode(NumberMode::Interval);
+        let mut run = |src: &str| LookAhead::try_from(src).unwrap().parse_program().unwrap().eval_with(&mut env);
+        assert!(matches!(run("x * 10±1"), Ok(Some(Number::Interval(i))) if i.lo() <= 18.0 && i.hi() >= 22.0));
+    }
+
+    #[test]
+    fn test_integer_mode() {
+        use super::env::Environment;
+        use super::error::{CalError, EvalError};
+        use super::number::{Integer, NumberMode};
+        use lookahead::LookAhead;
+
+        let env = Environment::<Integer>::default();
+        let run = |src: &str| LookAhead::try_from(src).unwrap().parse_expr().unwrap().eval_with(&env);
+
+        assert_eq!(run("2^100").unwrap().to_string(), "1267650600228229401496703205376");
+        assert_eq!(run("-7 % 3").unwrap().to_string(), "2");
+        assert_eq!(run("7 % -3").unwrap().to_string(), "-2");
+        assert_eq!(run("12/4").unwrap().to_string(), "3");
+        assert_eq!(run("factorial(25)").unwrap().to_string(), "15511210043330985984000000");
+        assert_eq!(
+            run("binomial(100, 50)").unwrap().to_string(),
+            "100891344545564193334812497256"
+        );
+        assert_eq!(run("binomial(5, 7)").unwrap().to_string(), "0");
+        assert_eq!(run("factorial(1000)").unwrap().to_string().len(), 2568);
+        assert!(matches!(run("7/2"), Err(CalError::EvalError(EvalError::NotInteger, _))));
+        assert!(matches!(run("2^-1"), Err(CalError::EvalError(EvalError::NotInteger, _))));
+        assert!(matches!(run("factorial(-1)"), Err(CalError::EvalError(EvalError::NotInteger, _))));
+        assert!(matches!(run("factorial(1000000)"), Err(CalError::EvalError(EvalError::TooLarge, _))));
+
+        // the runtime mode falls back to floating point instead of failing
+        let mut env = Environment::new();
+        env.set_mode(NumberMode::Integer);
+        let mut run = |src: &str| {
+            LookAhead::try_from(src)
+                .unwrap()
+                .parse_program()
+                .unwrap()
+                .eval_with(&mut env)
+                .unwrap()
+                .unwrap()
+        };
+        assert_eq!(run("3^50 - 1").to_string(), "717897987691852588770248");
+        assert!(matches!(run("2^64"), Number::Integer(_)));
+        assert_eq!(run("7/2"), Number::Float(3.5));
+        assert_eq!(run("1.5 * 2"), Number::Float(3.0));
+        assert_eq!(run("factorial(5) + 1").to_string(), "121");
+    }
+
+    #[test]
+    fn test_units() {
+        use super::env::Environment;
+        use super::error::{CalError, EvalError};
+        use super::number::{Decimal, Numeric, Quantity};
+        use super::unit::{Base, Dimension};
+        use lookahead::LookAhead;
+
+        let env = Environment::<Quantity<Decimal>>::default();
+        let run = |src: &str| LookAhead::try_from(src).unwrap().parse_expr().unwrap().eval_with(&env);
+
+        assert_eq!(run("3 km / 20 min to m/s").unwrap().to_string(), "2.5 m/s");
+        assert_eq!(run("5 ft to cm").unwrap().to_string(), "152.4 cm");
+        assert_eq!(run("5 in to cm").unwrap().to_string(), "12.7 cm");
+        assert_eq!(run("5in to cm").unwrap().to_string(), "12.7 cm");
+        assert_eq!(run("1 km + 250 m").unwrap().to_string(), "1250 m");
+        assert_eq!(run("2 kg * 3 m / s^2").unwrap().to_string(), "6 m*kg/s^2");
+        assert_eq!(run("(2 m)^2 to cm^2").unwrap().to_string(), "40000 cm^2");
+        assert_eq!(run("sqrt(9 m^2)").unwrap().to_string(), "3 m");
+        assert_eq!(run("1 h to s").unwrap().to_string(), "3600 s");
+        assert_eq!(run("10 m / 5 m").unwrap().to_string(), "2");
+        assert_eq!(run("-3 km").unwrap().to_string(), "-3000 m");
+        assert!((run("sin(90 deg)").unwrap().to_f64() - 1.0).abs() < 1e-12);
+
+        let mismatch = run("1 m + 2 s").unwrap_err();
+        let CalError::EvalError(EvalError::DimensionMismatch { expected, found }, span) = mismatch else {
+            panic!("expected a dimension mismatch, got {mismatch:?}");
+        };
+        assert_eq!((expected, found), (Dimension::base(Base::Length), Dimension::base(Base::Time)));
+        assert_eq!((span.start, span.end), (0, 9));
+        assert!(matches!(run("5 ft to s"), Err(CalError::EvalError(EvalError::DimensionMismatch { .. }, _))));
+        assert!(matches!(run("sin(2 m)"), Err(CalError::EvalError(EvalError::DimensionMismatch { .. }, _))));
+        assert!(matches!(run("3 furlong"), Err(CalError::EvalError(EvalError::UnknownUnit(_), _))));
+
+        // plain numbers have no dimensions to carry
+        let env = Environment::new();
+        let result = LookAhead::try_from("3 km").unwrap().parse_expr().unwrap().eval_with(&env);
+        assert!(matches!(result, Err(CalError::EvalError(EvalError::UnitsUnsupported, _))));
+    }
+
+    #[test]
+    fn test_currency() {
+        use super::currency::RateTable;
+        use super::env::Environment;
+        use super::error::{CalError, EvalError, RateError};
+        use super::number::{Decimal, Quantity};
+        use bigdecimal::BigDecimal;
+        use lookahead::LookAhead;
+        use std::str::FromStr;
+
+        let rates = RateTable::parse(
+            "# rates in US dollars
+            base USD
+            EUR 1.08
+            GBP 1.25 # pounds
+            JPY 0.0067
+            BHD 2.65",
+        )
+        .unwrap();
+        let mut env = Environment::<Quantity<Decimal>>::default();
+        env.set_rates(rates);
+        let run = |src: &str| LookAhead::try_from(src).unwrap().parse_expr().unwrap().eval_with(&env);
+
+        assert_eq!(run("12.50 USD + 3 EUR to GBP").unwrap().to_string(), "12.59 GBP");
+        assert_eq!(run("12.50 USD + 3 EUR").unwrap().to_string(), "15.74 USD");
+        assert_eq!(run("3 EUR * 2").unwrap().to_string(), "6.00 EUR");
+        assert_eq!(run("1000 JPY to USD").unwrap().to_string(), "6.70 USD");
+        assert_eq!(run("10 USD to JPY").unwrap().to_string(), "1493 JPY");
+        assert_eq!(run("1 GBP to BHD").unwrap().to_string(), "0.472 BHD");
+        assert_eq!(run("12 EUR / 3 EUR").unwrap().to_string(), "4");
+        assert!(matches!(run("5 CHF"), Err(CalError::EvalError(EvalError::MissingRate(code), _)) if code == "CHF"));
+        assert!(matches!(run("1 GBP to CHF"), Err(CalError::EvalError(EvalError::MissingRate(_), _))));
+        assert!(matches!(run("1 USD + 1 m"), Err(CalError::EvalError(EvalError::DimensionMismatch { .. }, _))));
+
+        assert!(matches!(RateTable::parse("EUR 1.08"), Err(RateError::MissingBase)));
+        assert!(matches!(
+            RateTable::parse("base USD\nEUR abc"),
+            Err(RateError::Syntax { line: 2, .. })
+        ));
+        assert!(matches!(RateTable::load("/nonexistent/rates.txt"), Err(RateError::Io(_))));
+        assert!(matches!(RateTable::parse("base USD\nEUR -1.08"), Err(RateError::Syntax { line: 2, .. })));
+        let mut rates = RateTable::default();
+        for rate in ["0", "-1.08"] {
+            let rate = BigDecimal::from_str(rate).unwrap();
+            assert!(matches!(rates.set_rate("EUR", &rate), Err(RateError::NotPositive(code)) if code == "EUR"));
+        }
+        assert!(rates.set_rate_with_decimals("EUR", &BigDecimal::from(0), 2).is_err());
+        assert!(!rates.contains("EUR"));
+
+        // without units there are no currencies, just unbound names
+        let env = Environment::new();
+        let result = LookAhead::try_from("ABC + 1").unwrap().parse_expr().unwrap().eval_with(&env);
+        assert!(matches!(result, Err(CalError::EvalError(EvalError::UnboundVariable(name), _)) if name == "ABC"));
+    }
+
+    #[test]
+    fn test_dates() {
+        use super::env::Environment;
+        use super::error::{CalError, EvalError, LexerError};
+        use super::number::{Decimal, Quantity};
+        use lookahead::LookAhead;
+
+        let env = Environment::<Quantity<Decimal>>::default();
+        let run = |src: &str| LookAhead::try_from(src).unwrap().parse_expr().unwrap().eval_with(&env);
+
+        assert_eq!(run("2026-10-18 + 45 days").unwrap().to_string(), "2026-12-02");
+        assert_eq!(run("(17:30 - 09:15) * 5").unwrap().to_string(), "41:15");
+        assert_eq!(run("17:30 - 09:15 to h").unwrap().to_string(), "8.25 h");
+        assert_eq!(run("2026-12-25 - 2026-10-18 to day").unwrap().to_string(), "68 day");
+        assert_eq!(run("2026-10-18 17:30 + 8 h").unwrap().to_string(), "2026-10-19 01:30");
+        assert_eq!(run("2026-10-18 + 17:30:05").unwrap().to_string(), "2026-10-18 17:30:05");
+        assert_eq!(run("2024-02-28 + 1 day").unwrap().to_string(), "2024-02-29");
+        assert_eq!(run("1969-12-31 - 1 week").unwrap().to_string(), "1969-12-24");
+        assert_eq!(run("max(2026-10-18, 2026-01-01)").unwrap().to_string(), "2026-10-18");
+
+        assert_eq!(run("weekday(2026-10-18)").unwrap().to_string(), "7");
+        assert_eq!(run("weekday(2000-01-01)").unwrap().to_string(), "6");
+        assert_eq!(run("workday(2026-10-16, 1)").unwrap().to_string(), "2026-10-19");
+        assert_eq!(run("workday(2026-10-18, 5)").unwrap().to_string(), "2026-10-23");
+        assert_eq!(run("workday(2026-10-19, -1)").unwrap().to_string(), "2026-10-16");
+        assert_eq!(run("workday(2026-10-14, 12)").unwrap().to_string(), "2026-10-30");
+        assert_eq!(run("workdays(2026-10-16, 2026-10-23)").unwrap().to_string(), "5");
+        assert_eq!(run("workdays(2026-10-23, 2026-10-16)").unwrap().to_string(), "-5");
+        assert_eq!(run("workdays(2026-10-17, 2026-10-18)").unwrap().to_string(), "0");
+
+        // without the exact shape of a date it is still a subtraction
+        assert_eq!(run("2026-10").unwrap().to_string(), "2016");
+        assert_eq!(run("2026-10-1").unwrap().to_string(), "2015");
+        assert_eq!(run("2026 - 10 - 18").unwrap().to_string(), "1998");
+        assert_eq!(run("2026-10-18e5").unwrap().to_string(), "-1797984");
+        // a date is not the duration since 1970, a clock is the duration it shows
+        assert!(run("2026-10-18").unwrap() != run("20744 day").unwrap());
+        assert_eq!(run("2026-10-18 - 1970-01-01 to day").unwrap().to_string(), "20744 day");
+        assert!(run("2026-10-18").unwrap() == run("2026-10-17 + 1 day").unwrap());
+        assert!(run("17:30").unwrap() == run("17.5 h").unwrap());
+        assert!(run("max(2026-10-18, 1 day)").is_err());
+
+        assert!(matches!(
+            run("2026-10-18 + 2026-10-18"),
+            Err(CalError::EvalError(EvalError::InvalidOperator(symbol), _)) if symbol == "+"
+        ));
+        assert!(matches!(
+            run("2026-10-18 * 2"),
+            Err(CalError::EvalError(EvalError::InvalidOperator(symbol), _)) if symbol == "*"
+        ));
+        assert!(matches!(run("2026-10-18 + 3"), Err(CalError::EvalError(EvalError::DimensionMismatch { .. }, _))));
+        assert!(matches!(run("weekday(5)"), Err(CalError::EvalError(EvalError::NotADate, _))));
+        assert!(matches!(
+            run("workday(2026-10-16, 2 days)"),
+            Err(CalError::EvalError(EvalError::DimensionMismatch { .. }, _))
+        ));
+        for src in ["2026-02-29", "2026-13-01", "17:60", "9:5"] {
+            assert!(
+                matches!(LookAhead::try_from(src), Err(CalError::LexError(LexerError::InvalidDate(_), _))),
+                "{src}"
+            );
+        }
+
+        let spans: Vec<_> = super::lexer::Lexer::from("2026-10*2")
+            .collect_spanned()
+            .unwrap()
+            .into_iter()
+            .map(|tok| (tok.span.start, tok.span.end))
+            .collect();
+        assert_eq!(spans, [(0, 4), (4, 5), (5, 7), (7, 8), (8, 9)]);
+
+        let env = Environment::new();
+        let result = LookAhead::try_from("2026-10-18").unwrap().parse_expr().unwrap().eval_with(&env);
+        assert!(matches!(result, Err(CalError::EvalError(EvalError::UnitsUnsupported, _))));
+        // plain numbers have no dates to count business days between
+        let result = LookAhead::try_from("weekday(5)").unwrap().parse_expr().unwrap().eval_with(&env);
+        assert!(matches!(result, Err(CalError::EvalError(EvalError::UnknownFunction(_), _))));
+    }
+
+    #[test]
+    fn test_number_literals() {
+        use super::error::{CalError, LexerError, NumberError};
+        use bigdecimal::BigDecimal;
+        use std::str::FromStr;
+
+        let number = |src: &str| match tokenize::<Vec<Token>>(src).unwrap().as_slice() {
+            [Token::Number(n)] => n.clone(),
+            tokens => panic!("expected one number for {src}, got {tokens:?}"),
+        };
+        assert_eq!(number("6.02e23"), BigDecimal::from_str("602000000000000000000000").unwrap());
+        assert_eq!(number("1.5E-3"), BigDecimal::from_str("0.0015").unwrap());
+        assert_eq!(number("2e+2"), BigDecimal::from(200));
+        assert_eq!(number("0xFF"), BigDecimal::from(255));
+        assert_eq!(number("0Xff_ff"), BigDecimal::from(65535));
+        assert_eq!(number("0b1010"), BigDecimal::from(10));
+        assert_eq!(number("0o17"), BigDecimal::from(15));
+        assert_eq!(number("1_000_000"), BigDecimal::from(1_000_000));
+        assert_eq!(number("3.141_592"), BigDecimal::from_str("3.141592").unwrap());
+        assert_eq!(number("0x1_0000_0000_0000_0000"), BigDecimal::from_str("18446744073709551616").unwrap());
+        assert_eq!(tokenize::<Vec<Token>>("1e3i").unwrap(), [Token::Imaginary(BigDecimal::from(1000))]);
+        // an `e` without exponent digits is not an exponent
+        assert_eq!(
+            tokenize::<Vec<Token>>("2em").unwrap(),
+            [Token::Number(BigDecimal::from(2)), Token::Ident(String::from("em"))]
+        );
+        assert_eq!(lookahead::LookAhead::try_from("2e3+1").unwrap().parse_expr().unwrap().eval().unwrap(), 2001.0);
+
+        let invalid = |src: &str| match tokenize::<Vec<Token>>(src) {
+            Err(CalError::LexError(LexerError::InvalidNumber(text, error), span)) => {
+                (text, error, span.start, span.end)
+            }
+            other => panic!("expected an invalid number for {src}, got {other:?}"),
+        };
+        assert_eq!(invalid("0x"), (String::from("0x"), NumberError::NoDigits, 0, 2));
+        assert_eq!(
+            invalid("1 + 0b102"),
+            (String::from("0b102"), NumberError::InvalidDigit { digit: '2', radix: 2 }, 4, 9)
+        );
+        assert_eq!(invalid("0o8").1, NumberError::InvalidDigit { digit: '8', radix: 8 });
+        assert_eq!(invalid("0xFG").1, NumberError::InvalidDigit { digit: 'G', radix: 16 });
+        assert_eq!(invalid("1__000").1, NumberError::Separator);
+        assert_eq!(invalid("1_000_").1, NumberError::Separator);
+        assert_eq!(invalid("1_.5").1, NumberError::Separator);
+        assert_eq!(invalid("0x_FF").1, NumberError::Separator);
+        assert_eq!(invalid("1.2.3").1, NumberError::ExtraPoint);
+        assert_eq!(invalid("1e+").1, NumberError::MissingExponent);
+        assert_eq!(invalid("2 * 1e-x"), (String::from("1e-"), NumberError::MissingExponent, 4, 7));
+        assert_eq!(invalid(".").1, NumberError::NoDigits);
+    }
+
+    #[test]
+    fn test_whitespace() {
+        use super::env::Environment;
+        use super::error::{CalError, LexerError};
+        use super::span::Span;
+        use lookahead::LookAhead;
+
+        let eval = |src: &str| LookAhead::try_from(src).unwrap().parse_expr().unwrap().eval().unwrap();
+        assert_eq!(eval("1 + 2"), 3.0);
+        assert_eq!(eval("\t2\u{a0}*\u{3000}(3 -\r\n1)\n"), 4.0);
+
+        // spans count the bytes of wide spaces
+        let mut lexer = Lexer::from("\u{3000}12");
+        assert_eq!(lexer.read().unwrap().span, Span::new(3, 5));
+
+        let mut ascii = Lexer::from("1\u{a0}+ 2").with_whitespace(is_ascii_space);
+        assert!(ascii.read().is_ok());
+        assert!(matches!(ascii.read(), Err(CalError::LexError(LexerError::UnknowChar('\u{a0}'), _))));
+
+        let tokens = |statements: bool| {
+            let mut lexer = Lexer::from("a\n b");
+            lexer.set_statements(statements);
+            lexer.collect().unwrap()
+        };
+        let ident = |name: &str| Token::Ident(String::from(name));
+        assert_eq!(tokens(false), vec![ident("a"), ident("b")]);
+        assert_eq!(tokens(true), vec![ident("a"), Token::Newline, ident("b")]);
+
+        let program = LookAhead::try_from("a = 1 +\u{2003}2\n b = a *\t a").unwrap().parse_program().unwrap();
+        assert_eq!(program.stmts().len(), 2);
+        assert_eq!(program.eval_with(&mut Environment::new()).unwrap(), Some(Number::from(9.0)));
+    }
+
+    #[test]
+    fn test_unicode_operators() {
+        use super::env::Environment;
+        use super::number::{Decimal, NumberMode, Quantity};
+        use lookahead::LookAhead;
+
+        assert_eq!(
+            tokenize::<Vec<Token>>("3×4÷2−1·5⋅6").unwrap(),
+            tokenize::<Vec<Token>>("3*4/2-1*5*6").unwrap()
+        );
+        assert_eq!(tokenize::<Vec<Token>>("x²³").unwrap()[1], Token::Superscript(23));
+        assert_eq!(tokenize::<Vec<Token>>("s⁻¹").unwrap()[1], Token::Superscript(-1));
+        assert_eq!(tokenize::<Vec<Token>>("√π").unwrap(), vec![Token::Root, Token::Ident(String::from("pi"))]);
+        assert!(tokenize::<Vec<Token>>("2⁻").is_err());
+
+        let mut env = Environment::new();
+        env.set_mode(NumberMode::Decimal { precision: 10 });
+        env.set("x", Number::from(3.0));
+        let eval = |src: &str| {
+            let expr = LookAhead::try_from(src).unwrap().parse_expr().unwrap();
+            expr.eval_with(&env).unwrap().to_string()
+        };
+        assert_eq!(eval("6 × 7"), "42");
+        assert_eq!(eval("1 ÷ 4 − 1"), "-0.75");
+        assert_eq!(eval("√9"), "3");
+        assert_eq!(eval("√(x² + 4²)"), "5");
+        assert_eq!(eval("x³ − 2²"), "23");
+        assert_eq!(eval("2 × π"), "6.283185308");
+        assert_eq!(eval("2⁻¹"), "0.5");
+
+        let env = Environment::<Quantity<Decimal>>::default();
+        let eval = |src: &str| {
+            let expr = LookAhead::try_from(src).unwrap().parse_expr().unwrap();
+            expr.eval_with(&env).unwrap().to_string()
+        };
+        assert_eq!(eval("3 m² to cm²"), "30000 cm^2");
+        assert_eq!(eval("10 m·s⁻¹ to km/h"), "36 km/h");
+    }
+
+    #[test]
+    fn test_implicit_multiplication() {
+        use super::env::Environment;
+        use super::number::{Decimal, NumberMode, Quantity};
+        use lookahead::{Implicit, LookAhead};
+
+        let mut env = Environment::new();
+        env.set_mode(NumberMode::Decimal { precision: 10 });
+        env.set("x", Number::from(3.0));
+        env.set("a", Number::from(2.0));
+        env.set("b", Number::from(5.0));
+        let parse = |src: &str, implicit| LookAhead::try_from(src).unwrap().with_implicit(implicit).parse_expr();
+        let eval = |src: &str, implicit| parse(src, implicit).unwrap().eval_with(&env).unwrap().to_string();
+
+        assert_eq!(eval("2(3+4)", Implicit::Tight), "14");
+        assert_eq!(eval("3pi", Implicit::Tight), "9.424777962");
+        assert_eq!(eval("(a)(b)", Implicit::Tight), "10");
+        assert_eq!(eval("a b + 1", Implicit::Tight), "11");
+        assert_eq!(eval("2x²", Implicit::Tight), "18");
+        assert_eq!(eval("2 sin(0) + 2sqrt(9)", Implicit::Tight), "6");
+        assert_eq!(eval("2√9", Implicit::Tight), "6");
+        assert_eq!(eval("2^3(4)", Implicit::Tight), "32");
+        assert_eq!(eval("1/2x", Implicit::Tight), "0.1666666667");
+        assert_eq!(eval("1/2x", Implicit::Loose), "1.5");
+        assert_eq!(eval("6/2(1+2)", Implicit::Tight), "1");
+        assert_eq!(eval("6/2(1+2)", Implicit::Loose), "9");
+        assert_eq!(eval("1 - 1/2x * 2", Implicit::Loose), "-2");
+        assert_eq!(eval("1/2sqrt(4)", Implicit::Tight), "0.25");
+        assert_eq!(eval("1/2sqrt(4)", Implicit::Loose), "1");
+        assert_eq!(eval("1 - 1/2 sqrt(4) * 2", Implicit::Loose), "-1");
+        assert_eq!(eval("2sqrt(9)^2", Implicit::Tight), "18");
+
+        assert!(parse("2(3)", Implicit::Strict).is_err());
+        assert!(parse("a b", Implicit::Strict).is_err());
+        assert!(parse("2 sin(0)", Implicit::Strict).is_err());
+        assert!(parse("2x", Implicit::Strict).unwrap().eval_with(&env).is_err());
+        assert_eq!(eval("2 * x", Implicit::Strict), "6");
+
+        let env = Environment::<Quantity<Decimal>>::default();
+        let eval = |src: &str, implicit| parse(src, implicit).unwrap().eval_with(&env).unwrap().to_string();
+        assert_eq!(eval("1/2 km", Implicit::Loose), "500 m");
+        assert_eq!(eval("(1 + 1) km", Implicit::Tight), "2000 m");
+        assert_eq!(eval("5 in to cm", Implicit::Strict), "12.7 cm");
+    }
+
+    #[test]
+    fn test_postfix_operators() {
+        use super::env::Environment;
+        use super::number::{Decimal, NumberMode, Quantity};
+        use lookahead::{LookAhead, Percent};
+
+        assert_eq!(
+            tokenize::<Vec<Token>>("3!!!").unwrap(),
+            vec![Token::Number(3.into()), Token::DoubleFactorial, Token::Factorial]
+        );
+
+        let mut env = Environment::new();
+        env.set_mode(NumberMode::Decimal { precision: 20 });
+        env.set("n", Number::from(4.0));
+        let parse = |src: &str, percent| LookAhead::try_from(src).unwrap().with_percent(percent).parse_expr();
+        let eval = |src: &str, percent| parse(src, percent).unwrap().eval_with(&env).unwrap().to_string();
+
+        assert_eq!(eval("5!", Percent::Modulo), "120");
+        assert_eq!(eval("n! / 2", Percent::Modulo), "12");
+        assert_eq!(eval("(1+2)!!", Percent::Modulo), "3");
+        assert_eq!(eval("8!! + 7!!", Percent::Modulo), "489");
+        assert_eq!(eval("0!!", Percent::Modulo), "1");
+        assert_eq!(eval("3!²", Percent::Modulo), "36");
+        assert_eq!(eval("25!", Percent::Modulo), "15511210043330985984000000");
+        assert!(parse("2.5!", Percent::Modulo).unwrap().eval_with(&env).is_err());
+
+        assert_eq!(eval("7 % 3", Percent::Modulo), "1");
+        assert!(parse("50%", Percent::Modulo).is_err());
+        assert_eq!(eval("50%", Percent::Calculator), "0.5");
+        assert_eq!(eval("200 + 10%", Percent::Calculator), "220");
+        assert_eq!(eval("200 - 10% * 2", Percent::Calculator), "199.8");
+        assert_eq!(eval("80 - 25%", Percent::Calculator), "60");
+        assert_eq!(eval("200 * 10%", Percent::Calculator), "20");
+        assert_eq!(eval("50% - 3", Percent::Calculator), "-2.5");
+
+        let env = Environment::<Quantity<Decimal>>::default();
+        let eval = |src: &str| parse(src, Percent::Calculator).unwrap().eval_with(&env).unwrap().to_string();
+        assert_eq!(eval("120 km + 5%"), "126000 m");
+        assert_eq!(eval("4 USD - 50%"), "2.00 USD");
+    }
+
+    #[test]
+    fn test_operator_conformance() {
+        use lookahead::LookAhead;
+
+        let eval = |src: &str| LookAhead::try_from(src).and_then(|mut parser| parser.parse_expr()?.eval());
+        // each expression and the value its grouping must give
+        let cases = [
+            // `^` is right-associative
+            ("2^3^2", 512.0),
+            ("(2^3)^2", 64.0),
+            ("4^3^0", 4.0),
+            ("2^-1^2", 0.5),
+            // other operators are left-associative
+            ("1-2-3", -4.0),
+            ("12/3/2", 2.0),
+            ("7%4%2", 1.0),
+            ("2*3^2", 18.0),
+            // a sign binds below `^` and above everything else
+            ("-2^2", -4.0),
+            ("(-2)^2", 4.0),
+            ("-2^2^3", -256.0),
+            ("-2^-2", -0.25),
+            ("2^-2", 0.25),
+            ("-2*3", -6.0),
+            ("2*-3", -6.0),
+            ("1+-2", -1.0),
+            ("1--2", 3.0),
+            ("-2+3", 1.0),
+            // any operand may follow a sign, and signs nest
+            ("-(1+2)", -3.0),
+            ("+(1+2)*2", 6.0),
+            ("--3", 3.0),
+            ("-+-3", 3.0),
+            ("-pi^0", -1.0),
+            ("-sqrt(4)", -2.0),
+            // postfix operators bind tightest
+            ("-3!", -6.0),
+            ("2^3!", 64.0),
+            ("-2²", -4.0),
+            ("2^3²", 512.0),
+            // adjacent operands bind between `^` and `*`
+            ("2(3)^2", 18.0),
+            ("6/2(1+2)", 1.0),
+            ("-2(3)", -6.0),
+        ];
+        for (src, expected) in cases {
+            assert_eq!(eval(src).unwrap(), expected, "{src}");
+        }
+
+        for src in ["-", "--", "2^", "2^-", "-*2", "(-)"] {
+            assert!(eval(src).is_err(), "{src}");
+        }
+    }
+
+    #[test]
+    fn test_operator_table() {
+        use super::env::Environment;
+        use super::error::{CalError, EvalError, LexerError};
+        use super::operator::{Assoc, Fixity};
+        use lookahead::LookAhead;
+
+        let mut env = Environment::<f64>::with_builtins();
+        let operators = env.operators_mut();
+        operators.register_infix("//", 10, Assoc::Left, "a // b: a / b rounded down", |a, b| Ok((a / b).floor()));
+        operators.register_infix("<<", 4, Assoc::Left, "a << b: a shifted by b bits", |a, b| Ok(a * 2f64.powf(*b)));
+        operators.register_infix("**", 15, Assoc::Right, "a ** b: a raised to b", |a, b| Ok(a.powf(*b)));
+        operators.register_infix("mod", 10, Assoc::Left, "a mod b: remainder", |a, b| Ok(a.rem_euclid(*b)));
+        operators.register_prefix("~", 15, "~a: a rounded", |a| Ok(a.round()));
+        operators.register_postfix("°", "a°: a in radians", |a| Ok(a.to_radians()));
+
+        let run = |src: &str, env: &Environment<f64>| {
+            LookAhead::with_grammar(src.chars(), env.operators().grammar())?.parse_expr()?.eval_with(env)
+        };
+        assert_eq!(run("7 // 2", &env).unwrap(), 3.0);
+        assert_eq!(run("-7//2", &env).unwrap(), -4.0);
+        assert_eq!(run("1/2", &env).unwrap(), 0.5);
+        assert_eq!(run("1 << 2 + 1", &env).unwrap(), 8.0);
+        assert_eq!(run("2 ** 3 ** 2", &env).unwrap(), 512.0);
+        assert_eq!(run("2 * 3 ** 2", &env).unwrap(), 18.0);
+        assert_eq!(run("-7 mod 3", &env).unwrap(), 2.0);
+        assert_eq!(run("~2.6 * 2", &env).unwrap(), 6.0);
+        assert_eq!(run("2~2.6", &env).unwrap(), 6.0);
+        assert_eq!(run("180° / pi", &env).unwrap(), 1.0);
+        assert!(matches!(
+            run("1 < 2", &env),
+            Err(CalError::LexError(LexerError::UnknowChar('<'), span)) if span.start == 2
+        ));
+
+        // the built-in grammar does not know them
+        assert!(LookAhead::try_from("7 // 2").unwrap().parse_expr().is_err());
+
+        // replacing and removing built-in operators
+        env.operators_mut().register_infix("^", 15, Assoc::Left, "a ^ b: a raised to b", |a, b| Ok(a.powf(*b)));
+        assert_eq!(run("2^3^2", &env).unwrap(), 64.0);
+        let parsed = LookAhead::try_from("7 % 2").unwrap().parse_expr().unwrap();
+        assert!(env.operators_mut().unregister("%", Fixity::Infix).is_some());
+        assert!(matches!(
+            parsed.eval_with(&env),
+            Err(CalError::EvalError(EvalError::UnknownOperator(symbol), _)) if symbol == "%"
+        ));
+    }
+
+    #[test]
+    fn test_parser_differential() {
+        use super::error::CalError;
+
+        fn legacy(src: &str) -> Option<f64> {
+            Tree::parse(tokenize(src).unwrap()).ok()?.calculate().ok()
+        }
+        fn adapter(src: &str) -> Option<f64> {
+            AST::parse(tokenize(src).unwrap()).ok()?.eval().ok()
+        }
+        fn rounded(num: Option<f64>) -> Option<f64> {
+            num.map(|num| (num * 1e8).round() / 1e8)
+        }
+        fn same(a: Option<f64>, b: Option<f64>) -> bool {
+            a == b || a.zip(b).is_some_and(|(a, b)| a.is_nan() && b.is_nan())
+        }
+
+        // xorshift, so every run checks the same expressions
+        fn next(seed: &mut u64) -> usize {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 7;
+            *seed ^= *seed << 17;
+            (*seed % 1024) as usize
+        }
+        // without `wide` only the forms both grammars read alike: at most one `+` or `-` per level and
+        // no `^` chains, with it also chains, a sign before `^`, `2(3)` and a `)` missing before more tokens
+        fn expr(seed: &mut u64, depth: u32, wide: bool) -> String {
+            let mut expr = term(seed, depth, wide);
+            for _ in 0..next(seed) % if wide { 3 } else { 2 } {
+                let op = [" + ", "-"][next(seed) % 2];
+                expr = format!("{expr}{op}{}", term(seed, depth, wide));
+            }
+            expr
+        }
+        fn term(seed: &mut u64, depth: u32, wide: bool) -> String {
+            let mut term = factor(seed, depth, wide);
+            for _ in 0..next(seed) % 3 {
+                let op = ["*", "/", "%"][next(seed) % 3];
+                term = format!("{term} {op} {}", factor(seed, depth, wide));
+            }
+            term
+        }
+        fn factor(seed: &mut u64, depth: u32, wide: bool) -> String {
+            let base = atom(seed, depth, wide);
+            match next(seed) % if wide { 7 } else { 5 } {
+                0 => format!("{base}^{}", atom(seed, depth, wide)),
+                1 => format!("{base}^-{}", atom(seed, depth, wide)),
+                2 => format!("-{base}"),
+                3 => format!("+-{base}"),
+                4 => base,
+                5 => format!("{base}^{}^{}", atom(seed, depth, wide), atom(seed, depth, wide)),
+                _ => format!("-{base}^{}", atom(seed, depth, wide)),
+            }
+        }
+        fn atom(seed: &mut u64, depth: u32, wide: bool) -> String {
+            match next(seed) % if wide { 5 } else { 3 } {
+                0 if depth > 0 => format!("({})", expr(seed, depth - 1, wide)),
+                3 if depth > 0 => format!("2({})", expr(seed, depth - 1, wide)),
+                4 if depth > 0 => format!("({} 5)", expr(seed, depth - 1, wide)),
+                _ => String::from(["0", "1", "2", "3", "0.5", "7", "10", "2.5", "1e3"][next(seed) % 9]),
+            }
+        }
+
+        let mut seed = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..500 {
+            let src = expr(&mut seed, 3, false);
+            assert!(same(parse(&src).ok().and_then(|ast| ast.eval().ok()), legacy(&src)), "{src}");
+            assert!(same(adapter(&src), rounded(legacy(&src))), "{src}");
+        }
+        // `AST` keeps the answers of the old grammar wherever `parse` reads it differently
+        for _ in 0..500 {
+            let src = expr(&mut seed, 2, true);
+            assert!(same(adapter(&src), rounded(legacy(&src))), "{src}");
+        }
+
+        // where `parse` groups differently, its answer is the one meant to replace the old
+        for (src, old, new) in [("1-2-3", 2.0, -4.0), ("2^3^2", 8.0, 512.0), ("-2^2", 4.0, -4.0), ("2(3)", 2.0, 6.0)] {
+            assert_eq!(adapter(src), Some(old), "{src}");
+            assert_eq!(parse(src).unwrap().eval().unwrap(), new, "{src}");
+        }
+        // the old grammar ignores a `)` missing before more tokens and tokens left over, `parse` rejects them
+        for (src, old) in [("(1 + 2 3", 3.0), ("2 * (3 + 4 5", 14.0), ("(1 2)", 1.0), ("1 2", 1.0)] {
+            assert_eq!(adapter(src), Some(old), "{src}");
+            assert!(parse(src).is_err(), "{src}");
+        }
+        assert!(matches!(parse("1 2"), Err(CalError::SyntaxError(_, span)) if span.start == 2));
+        assert!(adapter("(1 + 2").is_none());
+        assert_eq!(adapter("1e400 - 1"), Some(f64::INFINITY));
+        assert!(parse("(1 + 2").is_err());
+
+        // errors underline chars, however many bytes they take
+        assert!(AST::parse(tokenize("ééé + )").unwrap()).is_err());
+        let err = parse("ééé + )").unwrap_err();
+        assert_eq!(err.render("ééé + )").lines().last(), Some("      ^"));
+
+        let tree = Tree::parse(tokenize("1-2-3").unwrap()).unwrap();
+        #[allow(deprecated)]
+        let value = tree[tree.root()].calculate(&tree);
+        assert_eq!(value.unwrap(), 2.0);
+    }
+
+    #[test]
+    fn test_long_expressions() {
+        let sum = vec!["1"; 10_000].join("+");
+        let tree = Tree::parse(tokenize(&sum).unwrap()).unwrap();
+        assert_eq!(tree.calculate().unwrap(), 10_000.0);
+        // a number, a union, a power and a product per term, and one sum per term
+        assert_eq!(tree.len(), 5 * 10_000);
+        assert!(parse(&sum).is_ok());
+
+        let nested = format!("{}2{}", "(".repeat(100), "*3)".repeat(100));
+        let product = (0..100).fold(2.0, |product, _| product * 3.0);
+        assert_eq!(Tree::parse(tokenize(&nested).unwrap()).unwrap().calculate().unwrap(), product);
+        assert!(Tree::parse(vec![]).is_err());
+    }
+
+    #[test]
+    fn test_error_recovery() {
+        use super::env::Environment;
+        use super::error::{CalError, LexerError};
+        use lookahead::LookAhead;
+
+        let src = "1 + * 2\nlet x = (3\nf(1, 2\n4 )\n5 @ 6\nlet = 2\n3 * 2";
+        let (program, errors) = parse_recovering(src);
+        let line = |err: &CalError| src[..err.span().start].matches('\n').count();
+        assert_eq!(errors.iter().map(line).collect::<Vec<_>>(), [0, 1, 2, 3, 4, 5]);
+        assert!(matches!(&errors[0], CalError::SyntaxError(_, span) if span.start == 4 && span.end == 5));
+        assert!(matches!(&errors[4], CalError::LexError(LexerError::UnknowChar('@'), _)));
+
+        // the statements with errors fail as a whole, the ones without still run
+        let mut env = Environment::<f64>::with_builtins();
+        let results: Vec<_> = program.stmts().iter().map(|stmt| stmt.exec(&mut env)).collect();
+        assert_eq!(results.len(), 7);
+        let text = |result: &Result<Option<f64>, CalError>| match result {
+            Err(CalError::SyntaxError(_, span)) => &src[span.start..span.end],
+            other => panic!("{other:?}"),
+        };
+        let failed: Vec<_> = results[..6].iter().map(text).collect();
+        assert_eq!(failed, ["1 + * 2", "let x = (3", "f(1, 2", "4 )", "5 @ 6", "let = 2"]);
+        assert_eq!(results[6].as_ref().unwrap(), &Some(6.0));
+        assert!(env.get("x").is_none());
+
+        let (program, errors) = parse_recovering("(1 + 2");
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(program.eval_with(&mut env), Err(CalError::SyntaxError(..))));
+        let (program, errors) = parse_recovering("@");
+        assert_eq!((program.stmts().len(), errors.len()), (0, 1));
+        let (_, errors) = parse_recovering("1 + 2; max(1, 2)\n");
+        assert!(errors.is_empty());
+
+        // the other parsers still fail on the first error
+        let err = LookAhead::try_from(src).unwrap().parse_program().unwrap_err();
+        assert_eq!(err.span().start, 4);
+        assert!(parse("(1 + 2").is_err());
+    }
 }
@@ -0,0 +1,654 @@
+pub mod complex;
+pub mod decimal;
+pub mod float;
+pub mod integer;
+pub mod interval;
+pub mod quantity;
+pub mod rational;
+
+use std::{cmp::Ordering, fmt::Debug, fmt::Display};
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+use num_bigint::BigInt;
+use num_traits::One;
+
+pub use complex::Complex;
+pub use decimal::{Decimal, Precision};
+pub use integer::Integer;
+pub use interval::Interval;
+pub use quantity::Quantity;
+pub use rational::Rational;
+
+use super::{error::EvalError, unit::Unit};
+
+/// The arithmetic the evaluator needs from a number representation.
+///
+/// Every operation may fail, e.g. on a division by zero. Functions of the standard
+/// library default to going through `f64`; representations that can do better override them.
+pub trait Numeric: Clone + Debug + Display + PartialEq + 'static {
+    /// Settings shared by all numbers of one evaluation, e.g. the decimal precision.
+    type Mode: Copy + Debug + Default + PartialEq;
+
+    /// Convert a number literal as read by the lexer.
+    fn from_literal(lit: &BigDecimal, mode: Self::Mode) -> Result<Self, EvalError>;
+
+    /// Convert an imaginary literal like `2i`. Only complex representations support them.
+    fn from_imaginary(_lit: &BigDecimal, _mode: Self::Mode) -> Result<Self, EvalError> {
+        Err(EvalError::NotReal)
+    }
+
+    fn from_f64(value: f64, mode: Self::Mode) -> Result<Self, EvalError>;
+
+    fn to_f64(&self) -> f64;
+
+    /// The value as a non-negative integer, e.g. to take its factorial.
+    fn to_index(&self) -> Result<u64, EvalError> {
+        index_of_f64(self.to_f64())
+    }
+
+    /// The mode this number was created in.
+    fn mode(&self) -> Self::Mode;
+
+    /// The same number in the representation of `mode`.
+    fn to_mode(&self, mode: Self::Mode) -> Result<Self, EvalError> {
+        if self.mode() == mode {
+            return Ok(self.clone());
+        }
+        Self::from_f64(self.to_f64(), mode)
+    }
+
+    fn add(&self, rhs: &Self) -> Result<Self, EvalError>;
+
+    fn sub(&self, rhs: &Self) -> Result<Self, EvalError>;
+
+    fn mul(&self, rhs: &Self) -> Result<Self, EvalError>;
+
+    fn div(&self, rhs: &Self) -> Result<Self, EvalError>;
+
+    fn rem(&self, rhs: &Self) -> Result<Self, EvalError>;
+
+    fn pow(&self, rhs: &Self) -> Result<Self, EvalError>;
+
+    fn neg(&self) -> Result<Self, EvalError>;
+
+    /// `self ± rhs`, only supported by interval representations.
+    fn plus_minus(&self, _rhs: &Self) -> Result<Self, EvalError> {
+        Err(EvalError::InvalidOperator(String::from("±")))
+    }
+
+    /// The unit and date operations of representations implementing [`Measure`], `None` for plain numbers.
+    fn measures() -> Option<Measures<Self>> {
+        None
+    }
+
+    /// Apply `f` to the `f64` value of this number.
+    fn map_f64(&self, f: fn(f64) -> f64) -> Result<Self, EvalError> {
+        Self::from_f64(f(self.to_f64()), self.mode())
+    }
+
+    fn sqrt(&self) -> Result<Self, EvalError> {
+        self.map_f64(f64::sqrt)
+    }
+
+    fn exp(&self) -> Result<Self, EvalError> {
+        self.map_f64(f64::exp)
+    }
+
+    fn ln(&self) -> Result<Self, EvalError> {
+        self.map_f64(f64::ln)
+    }
+
+    fn log10(&self) -> Result<Self, EvalError> {
+        self.map_f64(f64::log10)
+    }
+
+    fn sin(&self) -> Result<Self, EvalError> {
+        self.map_f64(f64::sin)
+    }
+
+    fn cos(&self) -> Result<Self, EvalError> {
+        self.map_f64(f64::cos)
+    }
+
+    fn tan(&self) -> Result<Self, EvalError> {
+        self.map_f64(f64::tan)
+    }
+
+    fn abs(&self) -> Result<Self, EvalError> {
+        self.map_f64(f64::abs)
+    }
+
+    fn floor(&self) -> Result<Self, EvalError> {
+        self.map_f64(f64::floor)
+    }
+
+    fn ceil(&self) -> Result<Self, EvalError> {
+        self.map_f64(f64::ceil)
+    }
+
+    /// Round to the nearest integer, halves away from zero.
+    fn round(&self) -> Result<Self, EvalError> {
+        self.map_f64(f64::round)
+    }
+
+    /// `n!`, computed exactly and then converted like a literal.
+    fn factorial(&self) -> Result<Self, EvalError> {
+        let n = self.to_index()?;
+        Self::from_literal(&BigDecimal::from(factorial(n)?), self.mode())
+    }
+
+    /// `n!!`, the product of every other integer from `n` down, computed exactly.
+    fn double_factorial(&self) -> Result<Self, EvalError> {
+        let n = self.to_index()?;
+        Self::from_literal(&BigDecimal::from(double_factorial(n)?), self.mode())
+    }
+
+    /// The number of ways to choose `k` of `self` items, computed exactly.
+    fn binomial(&self, k: &Self) -> Result<Self, EvalError> {
+        let (n, k) = (self.to_index()?, k.to_index()?);
+        Self::from_literal(&BigDecimal::from(binomial(n, k)?), self.mode())
+    }
+
+    /// `None` if the numbers are not ordered, e.g. when one is NaN.
+    fn compare(&self, rhs: &Self) -> Option<Ordering> {
+        self.to_f64().partial_cmp(&rhs.to_f64())
+    }
+
+    fn format(&self, _format: &NumberFormat) -> String {
+        self.to_string()
+    }
+
+    /// Rounded to exactly `decimals` places, like `12.50` for an amount of money.
+    fn to_fixed(&self, decimals: u32) -> String {
+        format!("{:.*}", decimals as usize, self.to_f64())
+    }
+}
+
+/// Numbers with units and dates, which only [`Quantity`] supports.
+pub trait Measure: Numeric {
+    /// This many of `unit`, as in `3 km`.
+    fn with_unit(&self, unit: &Unit) -> Result<Self, EvalError>;
+
+    /// The same quantity expressed in `unit`, as in `5 ft to cm`.
+    fn convert(&self, unit: &Unit) -> Result<Self, EvalError>;
+
+    /// The date `seconds` after 1970-01-01 at midnight, as in `2026-10-18`.
+    fn from_date(seconds: i64, mode: Self::Mode) -> Result<Self, EvalError>;
+
+    /// A duration shown as hours and minutes, as in `17:30`.
+    fn from_clock(seconds: i64, mode: Self::Mode) -> Result<Self, EvalError>;
+
+    /// The seconds since 1970-01-01 at midnight, if this is a date.
+    fn to_date(&self) -> Result<i64, EvalError>;
+}
+
+/// The operations of a [`Measure`] as plain functions, so code generic over any [`Numeric`]
+/// can reach them through [`Numeric::measures`].
+pub struct Measures<N: Numeric> {
+    pub with_unit: fn(&N, &Unit) -> Result<N, EvalError>,
+    pub convert: fn(&N, &Unit) -> Result<N, EvalError>,
+    pub from_date: fn(i64, N::Mode) -> Result<N, EvalError>,
+    pub from_clock: fn(i64, N::Mode) -> Result<N, EvalError>,
+    pub to_date: fn(&N) -> Result<i64, EvalError>,
+}
+
+impl<N: Numeric> Clone for Measures<N> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N: Numeric> Copy for Measures<N> {}
+
+impl<N: Measure> Measures<N> {
+    pub fn of() -> Self {
+        Self {
+            with_unit: N::with_unit,
+            convert: N::convert,
+            from_date: N::from_date,
+            from_clock: N::from_clock,
+            to_date: N::to_date,
+        }
+    }
+}
+
+/// Significant digits kept by [`NumberMode::Decimal`] unless configured otherwise.
+pub const DEFAULT_PRECISION: u64 = 50;
+
+/// The largest `n` whose factorial is computed, which has about 456 000 digits.
+pub const MAX_FACTORIAL: u64 = 100_000;
+
+fn index_of_f64(value: f64) -> Result<u64, EvalError> {
+    if value.fract() != 0.0 || value < 0.0 || value >= u64::MAX as f64 {
+        return Err(EvalError::NotInteger);
+    }
+    Ok(value as u64)
+}
+
+/// `lo * (lo + 1) * ... * hi`, splitting the range so the big multiplications are balanced.
+fn product(lo: u64, hi: u64) -> BigInt {
+    if lo > hi {
+        return BigInt::one();
+    }
+    if hi - lo < 16 {
+        return (lo..=hi).map(BigInt::from).product();
+    }
+    let mid = lo + (hi - lo) / 2;
+    product(lo, mid) * product(mid + 1, hi)
+}
+
+fn factorial(n: u64) -> Result<BigInt, EvalError> {
+    if n > MAX_FACTORIAL {
+        return Err(EvalError::TooLarge);
+    }
+    Ok(product(1, n))
+}
+
+fn double_factorial(n: u64) -> Result<BigInt, EvalError> {
+    if n > MAX_FACTORIAL {
+        return Err(EvalError::TooLarge);
+    }
+    Ok((1..=n).rev().step_by(2).map(BigInt::from).product())
+}
+
+fn binomial(n: u64, k: u64) -> Result<BigInt, EvalError> {
+    if k > n {
+        return Ok(BigInt::ZERO);
+    }
+    let k = k.min(n - k);
+    Ok(product(n - k + 1, n) / factorial(k)?)
+}
+
+/// How numbers are represented while an expression is evaluated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberMode {
+    /// Binary floating point, fast but `0.1 + 0.2 != 0.3`
+    #[default]
+    Float,
+    /// Decimal numbers rounded to `precision` significant digits
+    Decimal { precision: u64 },
+    /// Exact fractions, falling back to floating point for irrational results like `2^0.5`
+    Rational,
+    /// Integers of any size for literals without a decimal point, falling
+    /// back to floating point for results like `7/2`
+    Integer,
+    /// Complex numbers of two floating point parts, so `sqrt(-1)` is `i`
+    Complex,
+    /// Intervals `[lo, hi]` enclosing the exact result, written `3.2±0.1`
+    Interval,
+}
+
+impl NumberMode {
+    pub fn decimal() -> Self {
+        Self::Decimal {
+            precision: DEFAULT_PRECISION,
+        }
+    }
+}
+
+/// How fractions are written when a [`Number`] is formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FractionStyle {
+    /// `7/3`
+    #[default]
+    Improper,
+    /// `2 1/3`
+    Mixed,
+}
+
+/// How complex numbers are written when a [`Number`] is formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ComplexStyle {
+    /// `1+i`
+    #[default]
+    Rectangular,
+    /// `1.4142135623730951*e^(0.7853981633974483i)`
+    Polar,
+}
+
+/// Options for [`Numeric::format`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NumberFormat {
+    pub fraction: FractionStyle,
+    pub complex: ComplexStyle,
+}
+
+/// A number whose representation is chosen at runtime by a [`NumberMode`].
+///
+/// Operands of different representations fall back to floating point,
+/// or to a complex number or an interval if either of them is one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Number {
+    Float(f64),
+    Decimal(Decimal),
+    Rational(Rational),
+    Integer(Integer),
+    Complex(Complex),
+    Interval(Interval),
+}
+
+impl From<f64> for Number {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+/// Apply the binary operation `$op` to two [`Number`]s. Exact representations
+/// give their own result for `$rational` and `$integer`, as some operations fall back to floats.
+macro_rules! binary {
+    ($lhs:expr, $rhs:expr, $op:ident) => {
+        binary!($lhs, $rhs, $op, |l: &Rational, r| l.$op(r).map(Number::Rational), |l: &Integer, r| {
+            l.$op(r).map(Number::Integer)
+        })
+    };
+    ($lhs:expr, $rhs:expr, $op:ident, $rational:expr, $integer:expr) => {
+        match ($lhs, $rhs) {
+            (Number::Decimal(l), Number::Decimal(r)) => l.$op(r).map(Number::Decimal),
+            (Number::Rational(l), Number::Rational(r)) => $rational(l, r),
+            (Number::Integer(l), Number::Integer(r)) => $integer(l, r),
+            (Number::Complex(_), _) | (_, Number::Complex(_)) => {
+                $lhs.to_complex().$op(&$rhs.to_complex()).map(Number::Complex)
+            }
+            (Number::Interval(_), _) | (_, Number::Interval(_)) => {
+                $lhs.to_interval()?.$op(&$rhs.to_interval()?).map(Number::Interval)
+            }
+            _ => Numeric::$op(&$lhs.to_f64(), &$rhs.to_f64()).map(Number::Float),
+        }
+    };
+}
+
+/// Apply `$op`, which gives exact results on every representation.
+macro_rules! exact {
+    ($num:expr, $op:ident) => {
+        match $num {
+            Number::Float(n) => Numeric::$op(n).map(Number::Float),
+            Number::Decimal(d) => d.$op().map(Number::Decimal),
+            Number::Rational(r) => r.$op().map(Number::Rational),
+            Number::Integer(i) => i.$op().map(Number::Integer),
+            Number::Complex(c) => c.$op().map(Number::Complex),
+            Number::Interval(i) => i.$op().map(Number::Interval),
+        }
+    };
+}
+
+impl Number {
+    /// An integer result, or `float` if there is none.
+    fn integer_or(result: Result<Integer, EvalError>, float: impl FnOnce() -> f64) -> Result<Self, EvalError> {
+        match result {
+            Err(EvalError::NotInteger) => Ok(Number::Float(float())),
+            result => result.map(Number::Integer),
+        }
+    }
+
+    fn to_complex(&self) -> Complex {
+        match self {
+            Number::Complex(c) => *c,
+            _ => Complex::new(self.to_f64(), 0.0),
+        }
+    }
+
+    fn to_interval(&self) -> Result<Interval, EvalError> {
+        match self {
+            Number::Interval(i) => Ok(*i),
+            _ => Interval::from_f64(self.to_f64(), ()),
+        }
+    }
+
+    /// Apply a function that is computed through `f64` unless the number is complex or an interval.
+    fn transcendental(
+        &self,
+        real: fn(f64) -> f64,
+        complex: fn(&Complex) -> Result<Complex, EvalError>,
+        interval: fn(&Interval) -> Result<Interval, EvalError>,
+    ) -> Result<Self, EvalError> {
+        match self {
+            Number::Complex(c) => complex(c).map(Number::Complex),
+            Number::Interval(i) => interval(i).map(Number::Interval),
+            _ => self.map_f64(real),
+        }
+    }
+}
+
+impl Numeric for Number {
+    type Mode = NumberMode;
+
+    fn from_literal(lit: &BigDecimal, mode: NumberMode) -> Result<Self, EvalError> {
+        match mode {
+            NumberMode::Float => Ok(Self::Float(lit.to_f64().unwrap_or(f64::NAN))),
+            NumberMode::Decimal { precision } => Decimal::from_literal(lit, Precision(precision)).map(Self::Decimal),
+            NumberMode::Rational => Rational::from_literal(lit, ()).map(Self::Rational),
+            NumberMode::Integer if lit.fractional_digit_count() > 0 => Ok(Self::Float(lit.to_f64().unwrap_or(f64::NAN))),
+            NumberMode::Integer => Integer::from_literal(lit, ()).map(Self::Integer),
+            NumberMode::Complex => Complex::from_literal(lit, ()).map(Self::Complex),
+            NumberMode::Interval => Interval::from_literal(lit, ()).map(Self::Interval),
+        }
+    }
+
+    fn from_imaginary(lit: &BigDecimal, mode: NumberMode) -> Result<Self, EvalError> {
+        match mode {
+            NumberMode::Complex => Complex::from_imaginary(lit, ()).map(Self::Complex),
+            _ => Err(EvalError::NotReal),
+        }
+    }
+
+    /// In rational and integer mode only integers are taken as exact, anything
+    /// else is assumed to be an irrational approximation and stays a float.
+    fn from_f64(value: f64, mode: NumberMode) -> Result<Self, EvalError> {
+        match mode {
+            NumberMode::Float => Ok(Self::Float(value)),
+            NumberMode::Decimal { precision } => Decimal::from_f64(value, Precision(precision)).map(Self::Decimal),
+            NumberMode::Rational if value.fract() == 0.0 => Rational::from_f64(value, ()).map(Self::Rational),
+            NumberMode::Integer if value.fract() == 0.0 => Integer::from_f64(value, ()).map(Self::Integer),
+            NumberMode::Rational | NumberMode::Integer => Ok(Self::Float(value)),
+            NumberMode::Complex => Complex::from_f64(value, ()).map(Self::Complex),
+            NumberMode::Interval => Interval::from_f64(value, ()).map(Self::Interval),
+        }
+    }
+
+    fn to_f64(&self) -> f64 {
+        match self {
+            Number::Float(n) => *n,
+            Number::Decimal(d) => d.to_f64(),
+            Number::Rational(r) => r.to_f64(),
+            Number::Integer(i) => i.to_f64(),
+            Number::Complex(c) => c.to_f64(),
+            Number::Interval(i) => i.to_f64(),
+        }
+    }
+
+    fn mode(&self) -> NumberMode {
+        match self {
+            Number::Float(_) => NumberMode::Float,
+            Number::Decimal(d) => NumberMode::Decimal {
+                precision: d.precision(),
+            },
+            Number::Rational(_) => NumberMode::Rational,
+            Number::Integer(_) => NumberMode::Integer,
+            Number::Complex(_) => NumberMode::Complex,
+            Number::Interval(_) => NumberMode::Interval,
+        }
+    }
+
+    fn to_mode(&self, mode: NumberMode) -> Result<Self, EvalError> {
+        match (self, mode) {
+            (Number::Float(_), NumberMode::Float)
+            | (Number::Rational(_), NumberMode::Rational)
+            | (Number::Integer(_), NumberMode::Integer)
+            | (Number::Complex(_), NumberMode::Complex)
+            | (Number::Interval(_), NumberMode::Interval) => Ok(self.clone()),
+            (Number::Complex(c), _) if !c.is_real() => Err(EvalError::NotReal),
+            (Number::Decimal(d), NumberMode::Decimal { precision }) => d.to_mode(Precision(precision)).map(Self::Decimal),
+            (Number::Decimal(d), NumberMode::Rational) => Rational::from_literal(d.value(), ()).map(Self::Rational),
+            (Number::Rational(r), NumberMode::Decimal { precision }) => {
+                let numer = Decimal::new(BigDecimal::from(r.numer().clone()), precision);
+                let denom = Decimal::new(BigDecimal::from(r.denom().clone()), precision);
+                numer.div(&denom).map(Self::Decimal)
+            }
+            (Number::Integer(i), NumberMode::Rational) => Ok(Self::Rational(Rational::from(i.value().clone()))),
+            (Number::Integer(i), NumberMode::Decimal { precision }) => {
+                Ok(Self::Decimal(Decimal::new(BigDecimal::from(i.value().clone()), precision)))
+            }
+            (Number::Rational(r), NumberMode::Integer) if r.is_integer() => {
+                Ok(Self::Integer(Integer::from(r.numer().clone())))
+            }
+            (Number::Decimal(d), NumberMode::Integer) => match Integer::from_decimal(d.value()) {
+                Some(i) => Ok(Self::Integer(i)),
+                None => Ok(Self::Float(d.to_f64())),
+            },
+            (_, mode) => Self::from_f64(self.to_f64(), mode),
+        }
+    }
+
+    fn add(&self, rhs: &Self) -> Result<Self, EvalError> {
+        binary!(self, rhs, add)
+    }
+
+    fn sub(&self, rhs: &Self) -> Result<Self, EvalError> {
+        binary!(self, rhs, sub)
+    }
+
+    fn mul(&self, rhs: &Self) -> Result<Self, EvalError> {
+        binary!(self, rhs, mul)
+    }
+
+    fn div(&self, rhs: &Self) -> Result<Self, EvalError> {
+        binary!(self, rhs, div, |l: &Rational, r| l.div(r).map(Number::Rational), |l: &Integer, r| {
+            Self::integer_or(l.div(r), || l.to_f64() / r.to_f64())
+        })
+    }
+
+    fn rem(&self, rhs: &Self) -> Result<Self, EvalError> {
+        binary!(self, rhs, rem)
+    }
+
+    fn pow(&self, rhs: &Self) -> Result<Self, EvalError> {
+        binary!(
+            self,
+            rhs,
+            pow,
+            |l: &Rational, r| match l.exact_pow(r)? {
+                Some(exact) => Ok(Number::Rational(exact)),
+                None => Ok(Number::Float(l.to_f64().powf(r.to_f64()))),
+            },
+            |l: &Integer, r| Self::integer_or(l.pow(r), || l.to_f64().powf(r.to_f64()))
+        )
+    }
+
+    fn neg(&self) -> Result<Self, EvalError> {
+        exact!(self, neg)
+    }
+
+    fn plus_minus(&self, rhs: &Self) -> Result<Self, EvalError> {
+        match (self, rhs) {
+            (Number::Interval(l), Number::Interval(r)) => l.plus_minus(r).map(Number::Interval),
+            _ => Err(EvalError::InvalidOperator(String::from("±"))),
+        }
+    }
+
+    fn to_index(&self) -> Result<u64, EvalError> {
+        match self {
+            Number::Integer(i) => i.to_index(),
+            _ => index_of_f64(self.to_f64()),
+        }
+    }
+
+    fn sqrt(&self) -> Result<Self, EvalError> {
+        match self {
+            Number::Float(n) => Numeric::sqrt(n).map(Number::Float),
+            Number::Decimal(d) => d.sqrt().map(Number::Decimal),
+            Number::Rational(r) => match r.exact_pow(&Rational::half())? {
+                Some(exact) => Ok(Number::Rational(exact)),
+                None => Ok(Number::Float(r.to_f64().sqrt())),
+            },
+            Number::Integer(i) => Self::integer_or(i.sqrt(), || i.to_f64().sqrt()),
+            Number::Complex(c) => c.sqrt().map(Number::Complex),
+            Number::Interval(i) => i.sqrt().map(Number::Interval),
+        }
+    }
+
+    fn exp(&self) -> Result<Self, EvalError> {
+        self.transcendental(f64::exp, Complex::exp, Interval::exp)
+    }
+
+    fn ln(&self) -> Result<Self, EvalError> {
+        self.transcendental(f64::ln, Complex::ln, Interval::ln)
+    }
+
+    fn log10(&self) -> Result<Self, EvalError> {
+        self.transcendental(f64::log10, Complex::log10, Interval::log10)
+    }
+
+    fn sin(&self) -> Result<Self, EvalError> {
+        self.transcendental(f64::sin, Complex::sin, Interval::sin)
+    }
+
+    fn cos(&self) -> Result<Self, EvalError> {
+        self.transcendental(f64::cos, Complex::cos, Interval::cos)
+    }
+
+    fn tan(&self) -> Result<Self, EvalError> {
+        self.transcendental(f64::tan, Complex::tan, Interval::tan)
+    }
+
+    fn abs(&self) -> Result<Self, EvalError> {
+        exact!(self, abs)
+    }
+
+    fn floor(&self) -> Result<Self, EvalError> {
+        exact!(self, floor)
+    }
+
+    fn ceil(&self) -> Result<Self, EvalError> {
+        exact!(self, ceil)
+    }
+
+    fn round(&self) -> Result<Self, EvalError> {
+        exact!(self, round)
+    }
+
+    fn compare(&self, rhs: &Self) -> Option<Ordering> {
+        match (self, rhs) {
+            (Number::Decimal(l), Number::Decimal(r)) => l.compare(r),
+            (Number::Rational(l), Number::Rational(r)) => l.compare(r),
+            (Number::Integer(l), Number::Integer(r)) => l.compare(r),
+            (Number::Complex(_), _) | (_, Number::Complex(_)) => self.to_complex().compare(&rhs.to_complex()),
+            (Number::Interval(_), _) | (_, Number::Interval(_)) => {
+                self.to_interval().ok()?.compare(&rhs.to_interval().ok()?)
+            }
+            _ => self.to_f64().partial_cmp(&rhs.to_f64()),
+        }
+    }
+
+    fn format(&self, format: &NumberFormat) -> String {
+        match self {
+            Number::Float(n) => n.format(format),
+            Number::Decimal(d) => d.format(format),
+            Number::Rational(r) => r.format(format),
+            Number::Integer(i) => i.format(format),
+            Number::Complex(c) => c.format(format),
+            Number::Interval(i) => i.format(format),
+        }
+    }
+
+    fn to_fixed(&self, decimals: u32) -> String {
+        match self {
+            Number::Decimal(d) => d.to_fixed(decimals),
+            Number::Rational(r) => r.to_fixed(decimals),
+            Number::Integer(i) => i.to_fixed(decimals),
+            _ => format!("{:.*}", decimals as usize, self.to_f64()),
+        }
+    }
+}
+
+impl Display for Number {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Number::Float(n) => write!(f, "{n}"),
+            Number::Decimal(d) => write!(f, "{d}"),
+            Number::Rational(r) => write!(f, "{r}"),
+            Number::Integer(i) => write!(f, "{i}"),
+            Number::Complex(c) => write!(f, "{c}"),
+            Number::Interval(i) => write!(f, "{i}"),
+        }
+    }
+}
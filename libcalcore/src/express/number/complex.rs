@@ -0,0 +1,214 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use bigdecimal::{BigDecimal, ToPrimitive};
+use num_complex::Complex64;
+
+use super::{ComplexStyle, NumberFormat, Numeric};
+use crate::express::error::EvalError;
+
+/// A complex number of two `f64` parts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Complex(Complex64);
+
+impl From<Complex64> for Complex {
+    fn from(value: Complex64) -> Self {
+        Self(value)
+    }
+}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Self {
+        Self(Complex64::new(re, im))
+    }
+
+    pub fn re(&self) -> f64 {
+        self.0.re
+    }
+
+    pub fn im(&self) -> f64 {
+        self.0.im
+    }
+
+    pub fn is_real(&self) -> bool {
+        self.0.im == 0.0
+    }
+
+    /// The magnitude and the angle in radians.
+    pub fn to_polar(&self) -> (f64, f64) {
+        self.0.to_polar()
+    }
+
+    /// Format as `r*e^(θi)`, which parses back to the same number.
+    pub fn to_polar_string(&self) -> String {
+        let (r, theta) = self.to_polar();
+        if r == 0.0 || theta == 0.0 {
+            return r.to_string();
+        }
+        format!("{r}*e^({theta}i)")
+    }
+
+    fn checked(value: Complex64) -> Result<Self, EvalError> {
+        if value.is_finite() {
+            Ok(Self(value))
+        } else {
+            Err(EvalError::NotFinite)
+        }
+    }
+
+    fn real(&self) -> Result<f64, EvalError> {
+        if self.is_real() {
+            Ok(self.0.re)
+        } else {
+            Err(EvalError::NotReal)
+        }
+    }
+}
+
+impl Numeric for Complex {
+    type Mode = ();
+
+    fn from_literal(lit: &BigDecimal, _mode: ()) -> Result<Self, EvalError> {
+        Ok(Self::new(lit.to_f64().unwrap_or(f64::NAN), 0.0))
+    }
+
+    fn from_imaginary(lit: &BigDecimal, _mode: ()) -> Result<Self, EvalError> {
+        Ok(Self::new(0.0, lit.to_f64().unwrap_or(f64::NAN)))
+    }
+
+    fn from_f64(value: f64, _mode: ()) -> Result<Self, EvalError> {
+        Ok(Self::new(value, 0.0))
+    }
+
+    /// The real part, or NaN if the number is not real.
+    fn to_f64(&self) -> f64 {
+        self.real().unwrap_or(f64::NAN)
+    }
+
+    fn mode(&self) {}
+
+    fn add(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(Self(self.0 + rhs.0))
+    }
+
+    fn sub(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(Self(self.0 - rhs.0))
+    }
+
+    fn mul(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(Self(self.0 * rhs.0))
+    }
+
+    fn div(&self, rhs: &Self) -> Result<Self, EvalError> {
+        if rhs.0 == Complex64::ZERO {
+            return Err(EvalError::DivisionByZero);
+        }
+        Ok(Self(self.0 / rhs.0))
+    }
+
+    /// Only defined for real operands.
+    fn rem(&self, rhs: &Self) -> Result<Self, EvalError> {
+        let (lhs, rhs) = (self.real()?, rhs.real()?);
+        if rhs == 0.0 {
+            return Err(EvalError::DivisionByZero);
+        }
+        Self::from_f64(lhs % rhs, ())
+    }
+
+    /// Real integer exponents are computed by repeated multiplication, so `i^2` is exactly `-1`.
+    fn pow(&self, rhs: &Self) -> Result<Self, EvalError> {
+        if self.0 == Complex64::ZERO {
+            return match rhs.0.re {
+                re if re > 0.0 => Ok(*self),
+                re if re == 0.0 && rhs.0.im == 0.0 => Self::from_f64(1.0, ()),
+                _ => Err(EvalError::DivisionByZero),
+            };
+        }
+        match rhs.real().ok().filter(|exp| exp.fract() == 0.0).and_then(|exp| exp.to_i32()) {
+            Some(exp) => Self::checked(self.0.powi(exp)),
+            None => Self::checked(self.0.powc(rhs.0)),
+        }
+    }
+
+    /// Subtracts from zero rather than flipping signs, so `-1` does not get a
+    /// negative zero imaginary part and `sqrt(-1)` stays on the principal branch.
+    fn neg(&self) -> Result<Self, EvalError> {
+        Ok(Self(Complex64::ZERO - self.0))
+    }
+
+    fn sqrt(&self) -> Result<Self, EvalError> {
+        Self::checked(self.0.sqrt())
+    }
+
+    fn exp(&self) -> Result<Self, EvalError> {
+        Self::checked(self.0.exp())
+    }
+
+    fn ln(&self) -> Result<Self, EvalError> {
+        Self::checked(self.0.ln())
+    }
+
+    fn log10(&self) -> Result<Self, EvalError> {
+        Self::checked(self.0.log10())
+    }
+
+    fn sin(&self) -> Result<Self, EvalError> {
+        Self::checked(self.0.sin())
+    }
+
+    fn cos(&self) -> Result<Self, EvalError> {
+        Self::checked(self.0.cos())
+    }
+
+    fn tan(&self) -> Result<Self, EvalError> {
+        Self::checked(self.0.tan())
+    }
+
+    /// The magnitude, a real number.
+    fn abs(&self) -> Result<Self, EvalError> {
+        Self::from_f64(self.0.norm(), ())
+    }
+
+    fn floor(&self) -> Result<Self, EvalError> {
+        Ok(Self::new(self.0.re.floor(), self.0.im.floor()))
+    }
+
+    fn ceil(&self) -> Result<Self, EvalError> {
+        Ok(Self::new(self.0.re.ceil(), self.0.im.ceil()))
+    }
+
+    fn round(&self) -> Result<Self, EvalError> {
+        Ok(Self::new(self.0.re.round(), self.0.im.round()))
+    }
+
+    /// Complex numbers are only ordered if both are real.
+    fn compare(&self, rhs: &Self) -> Option<Ordering> {
+        self.real().ok()?.partial_cmp(&rhs.real().ok()?)
+    }
+
+    fn format(&self, format: &NumberFormat) -> String {
+        match format.complex {
+            ComplexStyle::Rectangular => self.to_string(),
+            ComplexStyle::Polar => self.to_polar_string(),
+        }
+    }
+}
+
+/// Rectangular form, e.g. `3`, `2i`, `1-i` or `0.5+2i`.
+impl Display for Complex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Complex64 { re, im } = self.0;
+        if im == 0.0 {
+            return write!(f, "{re}");
+        }
+        if re != 0.0 {
+            write!(f, "{re}{}", if im < 0.0 { "-" } else { "+" })?;
+        } else if im < 0.0 {
+            write!(f, "-")?;
+        }
+        if im.abs() == 1.0 {
+            write!(f, "i")
+        } else {
+            write!(f, "{}i", im.abs())
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::{cmp::Ordering, fmt::Display, num::NonZeroU64};
+
+use bigdecimal::{BigDecimal, Context, FromPrimitive, RoundingMode, ToPrimitive, Zero};
+
+use super::{Numeric, DEFAULT_PRECISION};
+use crate::express::error::EvalError;
+
+/// A decimal number rounded to `precision` significant digits after every operation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decimal {
+    value: BigDecimal,
+    precision: u64,
+}
+
+/// The number of significant digits kept by [`Decimal`] arithmetic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Precision(pub u64);
+
+impl Default for Precision {
+    fn default() -> Self {
+        Self(DEFAULT_PRECISION)
+    }
+}
+
+impl Decimal {
+    pub fn new(value: BigDecimal, precision: u64) -> Self {
+        let precision = precision.max(1);
+        Self {
+            value: context(precision).round_decimal(value),
+            precision,
+        }
+    }
+
+    pub fn value(&self) -> &BigDecimal {
+        &self.value
+    }
+
+    pub fn precision(&self) -> u64 {
+        self.precision
+    }
+
+    fn with_value(&self, rhs: &Self, value: BigDecimal) -> Self {
+        Self::new(value, self.precision.max(rhs.precision))
+    }
+
+    fn with_scale_round(&self, mode: RoundingMode) -> Result<Self, EvalError> {
+        Ok(Self::new(self.value.with_scale_round(0, mode), self.precision))
+    }
+}
+
+impl Numeric for Decimal {
+    type Mode = Precision;
+
+    fn from_literal(lit: &BigDecimal, mode: Precision) -> Result<Self, EvalError> {
+        Ok(Self::new(lit.clone(), mode.0))
+    }
+
+    /// The decimal closest to the shortest representation of `value`,
+    /// so `0.1_f64` becomes `0.1` rather than its exact binary expansion.
+    fn from_f64(value: f64, mode: Precision) -> Result<Self, EvalError> {
+        if !value.is_finite() {
+            return Err(EvalError::NotFinite);
+        }
+        let value = value
+            .to_string()
+            .parse::<BigDecimal>()
+            .ok()
+            .or_else(|| BigDecimal::from_f64(value))
+            .ok_or(EvalError::NotFinite)?;
+        Ok(Self::new(value, mode.0))
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.value.to_f64().unwrap_or(f64::NAN)
+    }
+
+    fn mode(&self) -> Precision {
+        Precision(self.precision)
+    }
+
+    fn to_mode(&self, mode: Precision) -> Result<Self, EvalError> {
+        Ok(Self::new(self.value.clone(), mode.0))
+    }
+
+    fn add(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(self.with_value(rhs, &self.value + &rhs.value))
+    }
+
+    fn sub(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(self.with_value(rhs, &self.value - &rhs.value))
+    }
+
+    fn mul(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(self.with_value(rhs, &self.value * &rhs.value))
+    }
+
+    fn div(&self, rhs: &Self) -> Result<Self, EvalError> {
+        if rhs.value.is_zero() {
+            return Err(EvalError::DivisionByZero);
+        }
+        // a few guard digits keep the inverse from adding its own rounding error
+        let precision = self.precision.max(rhs.precision);
+        let inverse = rhs.value.inverse_with_context(&context(precision + 8));
+        Ok(self.with_value(rhs, &self.value * inverse))
+    }
+
+    fn rem(&self, rhs: &Self) -> Result<Self, EvalError> {
+        if rhs.value.is_zero() {
+            return Err(EvalError::DivisionByZero);
+        }
+        Ok(self.with_value(rhs, &self.value % &rhs.value))
+    }
+
+    /// Integer exponents are computed exactly (up to rounding), others through `f64`.
+    fn pow(&self, rhs: &Self) -> Result<Self, EvalError> {
+        let precision = self.precision.max(rhs.precision);
+        match rhs.value.is_integer().then(|| rhs.value.to_i64()).flatten() {
+            Some(exp) if exp < 0 && self.value.is_zero() => Err(EvalError::DivisionByZero),
+            Some(exp) => Ok(self.with_value(rhs, self.value.powi_with_context(exp, &context(precision + 8)))),
+            None => Self::from_f64(self.to_f64().powf(rhs.to_f64()), Precision(precision)),
+        }
+    }
+
+    fn neg(&self) -> Result<Self, EvalError> {
+        Ok(Self {
+            value: -&self.value,
+            precision: self.precision,
+        })
+    }
+
+    fn sqrt(&self) -> Result<Self, EvalError> {
+        let value = self
+            .value
+            .sqrt_with_context(&context(self.precision))
+            .ok_or(EvalError::NotFinite)?;
+        Ok(Self::new(value, self.precision))
+    }
+
+    fn abs(&self) -> Result<Self, EvalError> {
+        Ok(Self {
+            value: self.value.abs(),
+            precision: self.precision,
+        })
+    }
+
+    fn floor(&self) -> Result<Self, EvalError> {
+        self.with_scale_round(RoundingMode::Floor)
+    }
+
+    fn ceil(&self) -> Result<Self, EvalError> {
+        self.with_scale_round(RoundingMode::Ceiling)
+    }
+
+    fn round(&self) -> Result<Self, EvalError> {
+        self.with_scale_round(RoundingMode::HalfUp)
+    }
+
+    fn compare(&self, rhs: &Self) -> Option<Ordering> {
+        Some(self.value.cmp(&rhs.value))
+    }
+
+    /// Rounded half away from zero.
+    fn to_fixed(&self, decimals: u32) -> String {
+        self.value.with_scale_round(i64::from(decimals), RoundingMode::HalfUp).to_plain_string()
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value.normalized())
+    }
+}
+
+fn context(precision: u64) -> Context {
+    Context::new(NonZeroU64::new(precision.max(1)).unwrap(), RoundingMode::HalfEven)
+}
@@ -0,0 +1,51 @@
+use bigdecimal::{BigDecimal, ToPrimitive};
+
+use super::Numeric;
+use crate::express::error::EvalError;
+
+/// Plain IEEE 754 arithmetic: a division by zero gives an infinity rather than an error.
+impl Numeric for f64 {
+    type Mode = ();
+
+    fn from_literal(lit: &BigDecimal, _mode: ()) -> Result<Self, EvalError> {
+        Ok(lit.to_f64().unwrap_or(f64::NAN))
+    }
+
+    fn from_f64(value: f64, _mode: ()) -> Result<Self, EvalError> {
+        Ok(value)
+    }
+
+    fn to_f64(&self) -> f64 {
+        *self
+    }
+
+    fn mode(&self) {}
+
+    fn add(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(self + rhs)
+    }
+
+    fn sub(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(self - rhs)
+    }
+
+    fn mul(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(self * rhs)
+    }
+
+    fn div(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(self / rhs)
+    }
+
+    fn rem(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(self % rhs)
+    }
+
+    fn pow(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(self.powf(*rhs))
+    }
+
+    fn neg(&self) -> Result<Self, EvalError> {
+        Ok(-self)
+    }
+}
@@ -0,0 +1,168 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+use num_integer::Integer as _;
+use num_traits::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+
+use super::Numeric;
+use crate::express::error::EvalError;
+
+/// Results with more bits than this are refused rather than computed for minutes.
+pub const MAX_BITS: u64 = 1 << 26;
+
+/// An integer of any size. Operations without an integer result fail with
+/// [`EvalError::NotInteger`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Integer(BigInt);
+
+impl From<BigInt> for Integer {
+    fn from(value: BigInt) -> Self {
+        Self(value)
+    }
+}
+
+impl From<i64> for Integer {
+    fn from(value: i64) -> Self {
+        Self(BigInt::from(value))
+    }
+}
+
+impl Integer {
+    pub fn value(&self) -> &BigInt {
+        &self.0
+    }
+
+    /// The integer value of `lit`, if it has no fractional part.
+    pub fn from_decimal(lit: &BigDecimal) -> Option<Self> {
+        let (digits, scale) = lit.with_scale(0).into_bigint_and_exponent();
+        debug_assert_eq!(scale, 0);
+        (BigDecimal::from(digits.clone()) == *lit).then_some(Self(digits))
+    }
+}
+
+impl Numeric for Integer {
+    type Mode = ();
+
+    fn from_literal(lit: &BigDecimal, _mode: ()) -> Result<Self, EvalError> {
+        Self::from_decimal(lit).ok_or(EvalError::NotInteger)
+    }
+
+    fn from_f64(value: f64, _mode: ()) -> Result<Self, EvalError> {
+        if !value.is_finite() {
+            return Err(EvalError::NotFinite);
+        }
+        if value.fract() != 0.0 {
+            return Err(EvalError::NotInteger);
+        }
+        BigInt::from_f64(value).map(Self).ok_or(EvalError::NotFinite)
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.0.to_f64().unwrap_or(f64::NAN)
+    }
+
+    fn to_index(&self) -> Result<u64, EvalError> {
+        self.0.to_u64().ok_or(EvalError::NotInteger)
+    }
+
+    fn mode(&self) {}
+
+    fn add(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(Self(&self.0 + &rhs.0))
+    }
+
+    fn sub(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(Self(&self.0 - &rhs.0))
+    }
+
+    fn mul(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(Self(&self.0 * &rhs.0))
+    }
+
+    /// Only exact quotients, e.g. `6/3` but not `7/2`.
+    fn div(&self, rhs: &Self) -> Result<Self, EvalError> {
+        if rhs.0.is_zero() {
+            return Err(EvalError::DivisionByZero);
+        }
+        let (quotient, rem) = self.0.div_rem(&rhs.0);
+        if !rem.is_zero() {
+            return Err(EvalError::NotInteger);
+        }
+        Ok(Self(quotient))
+    }
+
+    /// The modulus with the sign of the divisor, so `-7 % 3` is `2`.
+    fn rem(&self, rhs: &Self) -> Result<Self, EvalError> {
+        if rhs.0.is_zero() {
+            return Err(EvalError::DivisionByZero);
+        }
+        Ok(Self(self.0.mod_floor(&rhs.0)))
+    }
+
+    /// Exact for non-negative exponents. Negative ones only give an integer for a base of ±1.
+    fn pow(&self, rhs: &Self) -> Result<Self, EvalError> {
+        if self.0.abs().is_one() {
+            let odd = rhs.0.is_odd();
+            return Ok(Self(if self.0.is_negative() && odd { -BigInt::one() } else { BigInt::one() }));
+        }
+        if rhs.0.is_negative() {
+            return Err(if self.0.is_zero() {
+                EvalError::DivisionByZero
+            } else {
+                EvalError::NotInteger
+            });
+        }
+        let exp = rhs.0.to_u32().ok_or(EvalError::TooLarge)?;
+        if self.0.bits().saturating_mul(u64::from(exp)) > MAX_BITS {
+            return Err(EvalError::TooLarge);
+        }
+        Ok(Self(self.0.pow(exp)))
+    }
+
+    fn neg(&self) -> Result<Self, EvalError> {
+        Ok(Self(-&self.0))
+    }
+
+    /// Exact for perfect squares.
+    fn sqrt(&self) -> Result<Self, EvalError> {
+        if self.0.is_negative() {
+            return Err(EvalError::NotFinite);
+        }
+        let root = self.0.sqrt();
+        if &root * &root != self.0 {
+            return Err(EvalError::NotInteger);
+        }
+        Ok(Self(root))
+    }
+
+    fn abs(&self) -> Result<Self, EvalError> {
+        Ok(Self(self.0.abs()))
+    }
+
+    fn floor(&self) -> Result<Self, EvalError> {
+        Ok(self.clone())
+    }
+
+    fn ceil(&self) -> Result<Self, EvalError> {
+        Ok(self.clone())
+    }
+
+    fn round(&self) -> Result<Self, EvalError> {
+        Ok(self.clone())
+    }
+
+    fn compare(&self, rhs: &Self) -> Option<Ordering> {
+        Some(self.cmp(rhs))
+    }
+
+    fn to_fixed(&self, decimals: u32) -> String {
+        BigDecimal::from(self.0.clone()).with_scale(i64::from(decimals)).to_plain_string()
+    }
+}
+
+impl Display for Integer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
@@ -0,0 +1,294 @@
+use std::{
+    cmp::Ordering,
+    f64::consts::{FRAC_PI_2, PI, TAU},
+    fmt::Display,
+};
+
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+
+use super::Numeric;
+use crate::express::error::EvalError;
+
+/// A closed interval `[lo, hi]` guaranteed to contain the exact result.
+///
+/// Every operation rounds its bounds outward by one ulp, and functions of the
+/// standard library by two, so the enclosure survives the rounding of `f64`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Interval {
+    lo: f64,
+    hi: f64,
+}
+
+impl Interval {
+    pub fn new(lo: f64, hi: f64) -> Result<Self, EvalError> {
+        if lo.is_nan() || hi.is_nan() || lo > hi {
+            return Err(EvalError::NotFinite);
+        }
+        Ok(Self { lo, hi })
+    }
+
+    /// The interval holding just `value`.
+    pub fn point(value: f64) -> Result<Self, EvalError> {
+        Self::new(value, value)
+    }
+
+    /// `center ± radius`
+    pub fn around(center: &Self, radius: &Self) -> Result<Self, EvalError> {
+        let radius = radius.mag();
+        Self::outward(center.lo - radius, center.hi + radius)
+    }
+
+    pub fn lo(&self) -> f64 {
+        self.lo
+    }
+
+    pub fn hi(&self) -> f64 {
+        self.hi
+    }
+
+    pub fn width(&self) -> f64 {
+        self.hi - self.lo
+    }
+
+    pub fn mid(&self) -> f64 {
+        self.lo + self.width() / 2.0
+    }
+
+    /// The largest absolute value in the interval.
+    pub fn mag(&self) -> f64 {
+        self.lo.abs().max(self.hi.abs())
+    }
+
+    pub fn contains(&self, value: f64) -> bool {
+        self.lo <= value && value <= self.hi
+    }
+
+    fn outward(lo: f64, hi: f64) -> Result<Self, EvalError> {
+        Self::outward_by(lo, hi, 1)
+    }
+
+    fn outward_by(mut lo: f64, mut hi: f64, ulps: usize) -> Result<Self, EvalError> {
+        for _ in 0..ulps {
+            lo = lo.next_down();
+            hi = hi.next_up();
+        }
+        Self::new(lo, hi)
+    }
+
+    /// The smallest interval holding `f(x)` for the given corners.
+    fn hull(corners: [f64; 4]) -> Result<Self, EvalError> {
+        if corners.iter().any(|x| x.is_nan()) {
+            return Err(EvalError::NotFinite);
+        }
+        let lo = corners.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = corners.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        Self::outward(lo, hi)
+    }
+
+    /// Apply a function increasing on the whole interval.
+    fn monotone(&self, f: fn(f64) -> f64) -> Result<Self, EvalError> {
+        Self::outward_by(f(self.lo), f(self.hi), 2)
+    }
+
+    /// Whether `offset + k * period` lies in the interval for some integer `k`.
+    fn hits(&self, offset: f64, period: f64) -> bool {
+        ((self.lo - offset) / period).ceil() <= ((self.hi - offset) / period).floor()
+    }
+
+    /// `sin` over the interval, bounded by the endpoints and any extremum inside it.
+    fn sine(&self, phase: f64) -> Result<Self, EvalError> {
+        if self.width() >= TAU {
+            return Self::new(-1.0, 1.0);
+        }
+        let (a, b) = ((self.lo + phase).sin(), (self.hi + phase).sin());
+        let lo = if self.hits(-FRAC_PI_2 - phase, TAU) { -1.0 } else { a.min(b) };
+        let hi = if self.hits(FRAC_PI_2 - phase, TAU) { 1.0 } else { a.max(b) };
+        Self::outward_by(lo, hi, 2).map(|i| Self {
+            lo: i.lo.max(-1.0),
+            hi: i.hi.min(1.0),
+        })
+    }
+
+    fn truncate(&self) -> Result<Self, EvalError> {
+        Self::new(self.lo.trunc(), self.hi.trunc())
+    }
+}
+
+impl Numeric for Interval {
+    type Mode = ();
+
+    /// The tightest interval of `f64` bounds holding the exact decimal value.
+    fn from_literal(lit: &BigDecimal, _mode: ()) -> Result<Self, EvalError> {
+        let value = lit.to_f64().ok_or(EvalError::NotFinite)?;
+        match BigDecimal::from_f64(value).map(|exact| exact.cmp(lit)) {
+            Some(Ordering::Equal) => Self::point(value),
+            Some(Ordering::Greater) => Self::new(value.next_down(), value),
+            _ => Self::new(value, value.next_up()),
+        }
+    }
+
+    /// `value` is taken to be rounded, so the interval spans one ulp to each side.
+    fn from_f64(value: f64, _mode: ()) -> Result<Self, EvalError> {
+        Self::outward(value, value)
+    }
+
+    /// The midpoint.
+    fn to_f64(&self) -> f64 {
+        self.mid()
+    }
+
+    fn mode(&self) {}
+
+    fn add(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Self::outward(self.lo + rhs.lo, self.hi + rhs.hi)
+    }
+
+    fn sub(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Self::outward(self.lo - rhs.hi, self.hi - rhs.lo)
+    }
+
+    fn mul(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Self::hull([self.lo * rhs.lo, self.lo * rhs.hi, self.hi * rhs.lo, self.hi * rhs.hi])
+    }
+
+    fn div(&self, rhs: &Self) -> Result<Self, EvalError> {
+        if rhs.lo == 0.0 && rhs.hi == 0.0 {
+            return Err(EvalError::DivisionByZero);
+        }
+        if rhs.contains(0.0) {
+            return Err(EvalError::StraddlesZero { lo: rhs.lo, hi: rhs.hi });
+        }
+        Self::hull([self.lo / rhs.lo, self.lo / rhs.hi, self.hi / rhs.lo, self.hi / rhs.hi])
+    }
+
+    /// The remainder truncated towards zero. If the quotient does not truncate to
+    /// one integer over the whole interval, only the sign and size are known.
+    fn rem(&self, rhs: &Self) -> Result<Self, EvalError> {
+        let quotient = self.div(rhs)?.truncate()?;
+        if quotient.lo == quotient.hi {
+            return self.sub(&rhs.mul(&quotient)?);
+        }
+        let bound = rhs.mag();
+        match (self.lo >= 0.0, self.hi <= 0.0) {
+            (true, _) => Self::new(0.0, bound),
+            (_, true) => Self::new(-bound, 0.0),
+            _ => Self::new(-bound, bound),
+        }
+    }
+
+    /// Integer exponents are exact up to rounding; others need a positive base.
+    fn pow(&self, rhs: &Self) -> Result<Self, EvalError> {
+        if rhs.lo == rhs.hi && rhs.lo.fract() == 0.0 {
+            if let Some(exp) = rhs.lo.to_i32() {
+                let power = |x: f64| x.powi(exp.abs());
+                let base = match exp.unsigned_abs() {
+                    0 => Self::point(1.0)?,
+                    n if n % 2 == 1 || self.lo >= 0.0 => Self::outward(power(self.lo), power(self.hi))?,
+                    _ if self.hi <= 0.0 => Self::outward(power(self.hi), power(self.lo))?,
+                    _ => Self::outward(0.0, power(self.mag()))?,
+                };
+                return if exp < 0 { Self::point(1.0)?.div(&base) } else { Ok(base) };
+            }
+        }
+        if self.lo < 0.0 {
+            return Err(EvalError::NotFinite);
+        }
+        // x^y is monotone in both arguments for positive x, and `powf` may be off by an ulp
+        let corners = [
+            self.lo.powf(rhs.lo),
+            self.lo.powf(rhs.hi),
+            self.hi.powf(rhs.lo),
+            self.hi.powf(rhs.hi),
+        ];
+        let hull = Self::hull(corners)?;
+        Self::outward(hull.lo, hull.hi)
+    }
+
+    fn neg(&self) -> Result<Self, EvalError> {
+        Self::new(-self.hi, -self.lo)
+    }
+
+    fn sqrt(&self) -> Result<Self, EvalError> {
+        if self.hi < 0.0 {
+            return Err(EvalError::NotFinite);
+        }
+        let lo = self.lo.max(0.0).sqrt().next_down().max(0.0);
+        Self::new(lo, self.hi.sqrt().next_up())
+    }
+
+    fn exp(&self) -> Result<Self, EvalError> {
+        self.monotone(f64::exp).map(|i| Self { lo: i.lo.max(0.0), ..i })
+    }
+
+    fn ln(&self) -> Result<Self, EvalError> {
+        if self.lo <= 0.0 {
+            return Err(EvalError::NotFinite);
+        }
+        self.monotone(f64::ln)
+    }
+
+    fn log10(&self) -> Result<Self, EvalError> {
+        if self.lo <= 0.0 {
+            return Err(EvalError::NotFinite);
+        }
+        self.monotone(f64::log10)
+    }
+
+    fn sin(&self) -> Result<Self, EvalError> {
+        self.sine(0.0)
+    }
+
+    fn cos(&self) -> Result<Self, EvalError> {
+        self.sine(FRAC_PI_2)
+    }
+
+    fn tan(&self) -> Result<Self, EvalError> {
+        if self.width() >= PI || self.hits(FRAC_PI_2, PI) {
+            return Err(EvalError::NotFinite);
+        }
+        self.monotone(f64::tan)
+    }
+
+    fn abs(&self) -> Result<Self, EvalError> {
+        match (self.lo >= 0.0, self.hi <= 0.0) {
+            (true, _) => Ok(*self),
+            (_, true) => self.neg(),
+            _ => Self::new(0.0, self.mag()),
+        }
+    }
+
+    fn floor(&self) -> Result<Self, EvalError> {
+        Self::new(self.lo.floor(), self.hi.floor())
+    }
+
+    fn ceil(&self) -> Result<Self, EvalError> {
+        Self::new(self.lo.ceil(), self.hi.ceil())
+    }
+
+    fn round(&self) -> Result<Self, EvalError> {
+        Self::new(self.lo.round(), self.hi.round())
+    }
+
+    fn plus_minus(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Self::around(self, rhs)
+    }
+
+    /// Ordered only if the intervals do not overlap, or are the same point.
+    fn compare(&self, rhs: &Self) -> Option<Ordering> {
+        if self.hi < rhs.lo {
+            Some(Ordering::Less)
+        } else if self.lo > rhs.hi {
+            Some(Ordering::Greater)
+        } else if self.lo == self.hi && self == rhs {
+            Some(Ordering::Equal)
+        } else {
+            None
+        }
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {}]", self.lo, self.hi)
+    }
+}
@@ -0,0 +1,394 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use bigdecimal::BigDecimal;
+
+use super::{Measure, Measures, Number, NumberFormat, Numeric};
+use crate::express::{
+    calendar,
+    error::EvalError,
+    unit::{Base, Dimension, Unit},
+};
+
+/// A number of SI units of some [`Dimension`], e.g. `2.5 m/s`.
+///
+/// Adding or comparing quantities of different dimensions fails with
+/// [`EvalError::DimensionMismatch`]. Functions like `sin` and `ln` only take plain numbers.
+///
+/// Amounts of money are shown in their currency, and anything converted with `to` in
+/// that unit. Results keep the unit of an operand with the same dimension.
+///
+/// Dates are points in time: a date plus a duration is a date, the difference of two
+/// dates a duration, and any other arithmetic on dates fails.
+#[derive(Debug, Clone)]
+pub struct Quantity<N: Numeric = Number> {
+    value: N,
+    dim: Dimension,
+    /// The unit the value is shown in, instead of SI units
+    unit: Option<Unit>,
+    kind: Kind,
+}
+
+/// What a quantity of time stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Amount,
+    /// Seconds since 1970-01-01 at midnight, shown like `2026-10-18 17:30`
+    Date,
+    /// A duration shown like `41:15`
+    Clock,
+}
+
+/// A date is never equal to a duration, while a clock equals the duration it shows.
+impl<N: Numeric> PartialEq for Quantity<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && self.dim == other.dim && self.is_date() == other.is_date()
+    }
+}
+
+impl<N: Numeric> From<N> for Quantity<N> {
+    fn from(value: N) -> Self {
+        Self::new(value, Dimension::NONE)
+    }
+}
+
+impl<N: Numeric> Quantity<N> {
+    pub fn new(value: N, dim: Dimension) -> Self {
+        Self {
+            value,
+            dim,
+            unit: None,
+            kind: Kind::Amount,
+        }
+    }
+
+    fn time(seconds: i64, mode: N::Mode, kind: Kind) -> Result<Self, EvalError> {
+        let value = N::from_literal(&BigDecimal::from(seconds), mode)?;
+        Ok(Self {
+            kind,
+            ..Self::new(value, Dimension::base(Base::Time))
+        })
+    }
+
+    pub fn is_date(&self) -> bool {
+        self.kind == Kind::Date
+    }
+
+    /// The value in SI units.
+    pub fn value(&self) -> &N {
+        &self.value
+    }
+
+    pub fn dim(&self) -> Dimension {
+        self.dim
+    }
+
+    /// The unit the value is shown in, if not SI units.
+    pub fn unit(&self) -> Option<&Unit> {
+        self.unit.as_ref()
+    }
+
+    /// The value in `unit`, e.g. `0.3048 m` in `ft` is `1`.
+    pub fn value_in(&self, unit: &Unit) -> Result<N, EvalError> {
+        self.expect_dim(unit.dim())?;
+        let (numer, denom) = self.factor(unit)?;
+        self.value.mul(&denom)?.div(&numer)
+    }
+
+    /// The numerator and denominator of the factor of `unit`, in the mode of this quantity.
+    fn factor(&self, unit: &Unit) -> Result<(N, N), EvalError> {
+        let mode = self.value.mode();
+        let numer = N::from_literal(&BigDecimal::from(unit.factor().numer().clone()), mode)?;
+        let denom = N::from_literal(&BigDecimal::from(unit.factor().denom().clone()), mode)?;
+        Ok((numer, denom))
+    }
+
+    fn expect_dim(&self, dim: Dimension) -> Result<(), EvalError> {
+        if self.dim != dim {
+            return Err(EvalError::DimensionMismatch {
+                expected: dim,
+                found: self.dim,
+            });
+        }
+        Ok(())
+    }
+
+    /// The plain number, failing for anything with a dimension.
+    fn plain(&self) -> Result<&N, EvalError> {
+        self.expect_dim(Dimension::NONE)?;
+        Ok(&self.value)
+    }
+
+    /// `value` of `dim`, shown in the unit of whichever operand has the same dimension,
+    /// or as a clock if that operand is one.
+    fn result(&self, rhs: Option<&Self>, value: N, dim: Dimension) -> Self {
+        let operands = || [Some(self), rhs].into_iter().flatten().filter(|operand| operand.dim == dim);
+        let unit = operands().find_map(|operand| operand.unit.clone());
+        let kind = if operands().any(|operand| operand.kind == Kind::Clock) {
+            Kind::Clock
+        } else {
+            Kind::Amount
+        };
+        Self { value, dim, unit, kind }
+    }
+
+    fn same(&self, rhs: &Self, op: fn(&N, &N) -> Result<N, EvalError>) -> Result<Self, EvalError> {
+        rhs.expect_dim(self.dim)?;
+        Ok(self.result(Some(rhs), op(&self.value, &rhs.value)?, self.dim))
+    }
+
+    fn keep(&self, op: impl Fn(&N) -> Result<N, EvalError>) -> Result<Self, EvalError> {
+        Ok(Self {
+            kind: self.kind,
+            ..self.result(None, op(&self.value)?, self.dim)
+        })
+    }
+
+    /// Fail with `symbol` as the invalid operator if either operand is a date.
+    fn no_dates(&self, rhs: &Self, symbol: &str) -> Result<(), EvalError> {
+        if self.is_date() || rhs.is_date() {
+            return Err(EvalError::InvalidOperator(String::from(symbol)));
+        }
+        Ok(())
+    }
+
+    fn with_kind(self, kind: Kind) -> Self {
+        Self { kind, ..self }
+    }
+
+    fn map_plain(&self, op: fn(&N) -> Result<N, EvalError>) -> Result<Self, EvalError> {
+        op(self.plain()?).map(Self::from)
+    }
+}
+
+impl<N: Numeric> Numeric for Quantity<N> {
+    type Mode = N::Mode;
+
+    fn from_literal(lit: &BigDecimal, mode: N::Mode) -> Result<Self, EvalError> {
+        N::from_literal(lit, mode).map(Self::from)
+    }
+
+    fn from_imaginary(lit: &BigDecimal, mode: N::Mode) -> Result<Self, EvalError> {
+        N::from_imaginary(lit, mode).map(Self::from)
+    }
+
+    fn from_f64(value: f64, mode: N::Mode) -> Result<Self, EvalError> {
+        N::from_f64(value, mode).map(Self::from)
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.value.to_f64()
+    }
+
+    fn to_index(&self) -> Result<u64, EvalError> {
+        self.plain()?.to_index()
+    }
+
+    fn mode(&self) -> N::Mode {
+        self.value.mode()
+    }
+
+    fn to_mode(&self, mode: N::Mode) -> Result<Self, EvalError> {
+        self.keep(|value| value.to_mode(mode))
+    }
+
+    /// A date plus a duration is a date.
+    fn add(&self, rhs: &Self) -> Result<Self, EvalError> {
+        let sum = self.same(rhs, N::add)?;
+        match (self.is_date(), rhs.is_date()) {
+            (true, true) => Err(EvalError::InvalidOperator(String::from("+"))),
+            (false, false) => Ok(sum),
+            _ => Ok(sum.with_kind(Kind::Date)),
+        }
+    }
+
+    /// The difference of two dates is a duration, and a date minus a duration a date.
+    fn sub(&self, rhs: &Self) -> Result<Self, EvalError> {
+        let difference = self.same(rhs, N::sub)?;
+        match (self.is_date(), rhs.is_date()) {
+            (true, true) => Ok(difference.with_kind(Kind::Amount)),
+            (true, false) => Ok(difference.with_kind(Kind::Date)),
+            (false, true) => Err(EvalError::InvalidOperator(String::from("-"))),
+            (false, false) => Ok(difference),
+        }
+    }
+
+    fn mul(&self, rhs: &Self) -> Result<Self, EvalError> {
+        self.no_dates(rhs, "*")?;
+        Ok(self.result(Some(rhs), self.value.mul(&rhs.value)?, self.dim.mul(&rhs.dim)?))
+    }
+
+    fn div(&self, rhs: &Self) -> Result<Self, EvalError> {
+        self.no_dates(rhs, "/")?;
+        Ok(self.result(Some(rhs), self.value.div(&rhs.value)?, self.dim.div(&rhs.dim)?))
+    }
+
+    fn rem(&self, rhs: &Self) -> Result<Self, EvalError> {
+        self.no_dates(rhs, "%")?;
+        self.same(rhs, N::rem)
+    }
+
+    /// Quantities with a dimension can only be raised to integer powers.
+    fn pow(&self, rhs: &Self) -> Result<Self, EvalError> {
+        self.no_dates(rhs, "^")?;
+        let exp = rhs.plain()?;
+        if self.dim.is_none() {
+            return self.value.pow(exp).map(Self::from);
+        }
+        let n = exp.to_f64();
+        if n.fract() != 0.0 || n.abs() > f64::from(i8::MAX) {
+            return Err(EvalError::NotInteger);
+        }
+        Ok(Self::new(self.value.pow(exp)?, self.dim.powi(n as i32)?))
+    }
+
+    fn neg(&self) -> Result<Self, EvalError> {
+        if self.is_date() {
+            return Err(EvalError::InvalidOperator(String::from("-")));
+        }
+        self.keep(N::neg)
+    }
+
+    fn plus_minus(&self, rhs: &Self) -> Result<Self, EvalError> {
+        self.no_dates(rhs, "±")?;
+        self.same(rhs, N::plus_minus)
+    }
+
+    fn measures() -> Option<Measures<Self>> {
+        Some(Measures::of())
+    }
+
+    /// Even powers of units have a square root, e.g. `sqrt(4 m^2)` is `2 m`.
+    fn sqrt(&self) -> Result<Self, EvalError> {
+        let dim = self.dim.root(2).ok_or(EvalError::DimensionMismatch {
+            expected: Dimension::NONE,
+            found: self.dim,
+        })?;
+        Ok(Self::new(self.value.sqrt()?, dim))
+    }
+
+    fn exp(&self) -> Result<Self, EvalError> {
+        self.map_plain(N::exp)
+    }
+
+    fn ln(&self) -> Result<Self, EvalError> {
+        self.map_plain(N::ln)
+    }
+
+    fn log10(&self) -> Result<Self, EvalError> {
+        self.map_plain(N::log10)
+    }
+
+    fn sin(&self) -> Result<Self, EvalError> {
+        self.map_plain(N::sin)
+    }
+
+    fn cos(&self) -> Result<Self, EvalError> {
+        self.map_plain(N::cos)
+    }
+
+    fn tan(&self) -> Result<Self, EvalError> {
+        self.map_plain(N::tan)
+    }
+
+    fn abs(&self) -> Result<Self, EvalError> {
+        self.keep(N::abs)
+    }
+
+    fn floor(&self) -> Result<Self, EvalError> {
+        self.keep(N::floor)
+    }
+
+    fn ceil(&self) -> Result<Self, EvalError> {
+        self.keep(N::ceil)
+    }
+
+    fn round(&self) -> Result<Self, EvalError> {
+        self.keep(N::round)
+    }
+
+    fn factorial(&self) -> Result<Self, EvalError> {
+        self.map_plain(N::factorial)
+    }
+
+    fn double_factorial(&self) -> Result<Self, EvalError> {
+        self.map_plain(N::double_factorial)
+    }
+
+    fn binomial(&self, k: &Self) -> Result<Self, EvalError> {
+        self.plain()?.binomial(k.plain()?).map(Self::from)
+    }
+
+    /// Quantities of different dimensions are not ordered, nor are dates and durations.
+    fn compare(&self, rhs: &Self) -> Option<Ordering> {
+        let comparable = self.dim == rhs.dim && self.is_date() == rhs.is_date();
+        comparable.then(|| self.value.compare(&rhs.value)).flatten()
+    }
+
+    /// Dates as dates, other values in their unit, rounded to the decimals of a currency,
+    /// or else as a clock or in SI units.
+    fn format(&self, format: &NumberFormat) -> String {
+        let seconds = || self.value.to_f64().round() as i64;
+        if self.kind == Kind::Date {
+            return calendar::format_date(seconds());
+        }
+        if let Some(unit) = &self.unit {
+            if let Ok(value) = self.value_in(unit) {
+                let value = match unit.decimals() {
+                    Some(decimals) => value.to_fixed(decimals),
+                    None => value.format(format),
+                };
+                return format!("{value} {}", unit.label());
+            }
+        }
+        if self.kind == Kind::Clock {
+            calendar::format_clock(seconds())
+        } else if self.dim.is_none() {
+            self.value.format(format)
+        } else {
+            format!("{} {}", self.value.format(format), self.dim)
+        }
+    }
+}
+
+impl<N: Numeric> Measure for Quantity<N> {
+    /// Currencies are remembered to show the amount in, other units are converted to SI.
+    fn with_unit(&self, unit: &Unit) -> Result<Self, EvalError> {
+        let (numer, denom) = self.factor(unit)?;
+        let value = self.value.mul(&numer)?.div(&denom)?;
+        let mut quantity = Self::new(value, self.dim.mul(&unit.dim())?);
+        if unit.decimals().is_some() && self.dim.is_none() {
+            quantity.unit = Some(unit.clone());
+        }
+        Ok(quantity)
+    }
+
+    fn convert(&self, unit: &Unit) -> Result<Self, EvalError> {
+        self.expect_dim(unit.dim())?;
+        Ok(Self {
+            unit: Some(unit.clone()),
+            ..self.clone()
+        })
+    }
+
+    fn from_date(seconds: i64, mode: N::Mode) -> Result<Self, EvalError> {
+        Self::time(seconds, mode, Kind::Date)
+    }
+
+    fn from_clock(seconds: i64, mode: N::Mode) -> Result<Self, EvalError> {
+        Self::time(seconds, mode, Kind::Clock)
+    }
+
+    fn to_date(&self) -> Result<i64, EvalError> {
+        if !self.is_date() {
+            return Err(EvalError::NotADate);
+        }
+        Ok(self.value.to_f64().round() as i64)
+    }
+}
+
+/// The value in the unit it was converted to, or in SI units.
+impl<N: Numeric> Display for Quantity<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.format(&NumberFormat::default()))
+    }
+}
@@ -0,0 +1,211 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Pow, Signed, ToPrimitive, Zero};
+
+use super::{FractionStyle, NumberFormat, Numeric};
+use crate::express::error::EvalError;
+
+/// An exact fraction of two big integers, always kept in lowest terms.
+///
+/// Results that are not rational, like `sqrt(2)`, are approximated by the
+/// fraction equal to the nearest `f64`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Rational(BigRational);
+
+impl From<BigRational> for Rational {
+    fn from(value: BigRational) -> Self {
+        Self(value)
+    }
+}
+
+impl From<BigInt> for Rational {
+    fn from(value: BigInt) -> Self {
+        Self(BigRational::from_integer(value))
+    }
+}
+
+impl Rational {
+    pub fn new(numer: BigInt, denom: BigInt) -> Result<Self, EvalError> {
+        if denom.is_zero() {
+            return Err(EvalError::DivisionByZero);
+        }
+        Ok(Self(BigRational::new(numer, denom)))
+    }
+
+    pub fn half() -> Self {
+        Self(BigRational::new(BigInt::one(), BigInt::from(2)))
+    }
+
+    pub fn value(&self) -> &BigRational {
+        &self.0
+    }
+
+    pub fn numer(&self) -> &BigInt {
+        self.0.numer()
+    }
+
+    pub fn denom(&self) -> &BigInt {
+        self.0.denom()
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.0.is_integer()
+    }
+
+    /// `self ^ rhs` if the result is rational, e.g. `(9/4)^(1/2) = 3/2`,
+    /// otherwise `None`.
+    pub fn exact_pow(&self, rhs: &Self) -> Result<Option<Self>, EvalError> {
+        let (Some(numer), Some(denom)) = (rhs.numer().to_i32(), rhs.denom().to_u32()) else {
+            return Ok(None);
+        };
+        if numer < 0 && self.0.is_zero() {
+            return Err(EvalError::DivisionByZero);
+        }
+        // the denominator of the exponent is a root, which must come out exact
+        let root = if denom == 1 {
+            self.clone()
+        } else {
+            if self.0.is_negative() && denom % 2 == 0 {
+                return Ok(None);
+            }
+            match (exact_root(self.numer(), denom), exact_root(self.denom(), denom)) {
+                (Some(n), Some(d)) => Self(BigRational::new(n, d)),
+                _ => return Ok(None),
+            }
+        };
+        Ok(Some(Self(root.0.pow(numer))))
+    }
+
+    /// Format as a mixed number, e.g. `7/3` as `2 1/3`.
+    pub fn to_mixed_string(&self) -> String {
+        let whole = self.0.trunc();
+        if whole.is_zero() || self.is_integer() {
+            return self.to_string();
+        }
+        let fract = (&self.0 - &whole).abs();
+        format!("{} {}", whole.numer(), fract)
+    }
+}
+
+impl Numeric for Rational {
+    type Mode = ();
+
+    /// The exact value of a decimal literal, e.g. `0.25` is `1/4`.
+    fn from_literal(lit: &BigDecimal, _mode: ()) -> Result<Self, EvalError> {
+        let (digits, scale) = lit.as_bigint_and_exponent();
+        let ten = BigInt::from(10);
+        let exp = u32::try_from(scale.unsigned_abs()).map_err(|_| EvalError::NotFinite)?;
+        if scale >= 0 {
+            Ok(Self(BigRational::new(digits, ten.pow(exp))))
+        } else {
+            Ok(Self::from(digits * ten.pow(exp)))
+        }
+    }
+
+    fn from_f64(value: f64, _mode: ()) -> Result<Self, EvalError> {
+        BigRational::from_float(value).map(Self).ok_or(EvalError::NotFinite)
+    }
+
+    fn to_f64(&self) -> f64 {
+        self.0.to_f64().unwrap_or(f64::NAN)
+    }
+
+    fn mode(&self) {}
+
+    fn add(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(Self(&self.0 + &rhs.0))
+    }
+
+    fn sub(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(Self(&self.0 - &rhs.0))
+    }
+
+    fn mul(&self, rhs: &Self) -> Result<Self, EvalError> {
+        Ok(Self(&self.0 * &rhs.0))
+    }
+
+    fn div(&self, rhs: &Self) -> Result<Self, EvalError> {
+        if rhs.0.is_zero() {
+            return Err(EvalError::DivisionByZero);
+        }
+        Ok(Self(&self.0 / &rhs.0))
+    }
+
+    /// The remainder truncated towards zero, like `%` on `f64`.
+    fn rem(&self, rhs: &Self) -> Result<Self, EvalError> {
+        if rhs.0.is_zero() {
+            return Err(EvalError::DivisionByZero);
+        }
+        Ok(Self(&self.0 % &rhs.0))
+    }
+
+    fn pow(&self, rhs: &Self) -> Result<Self, EvalError> {
+        match self.exact_pow(rhs)? {
+            Some(exact) => Ok(exact),
+            None => Self::from_f64(self.to_f64().powf(rhs.to_f64()), ()),
+        }
+    }
+
+    fn neg(&self) -> Result<Self, EvalError> {
+        Ok(Self(-&self.0))
+    }
+
+    fn sqrt(&self) -> Result<Self, EvalError> {
+        match self.exact_pow(&Self::half())? {
+            Some(exact) => Ok(exact),
+            None => self.map_f64(f64::sqrt),
+        }
+    }
+
+    fn abs(&self) -> Result<Self, EvalError> {
+        Ok(Self(self.0.abs()))
+    }
+
+    fn floor(&self) -> Result<Self, EvalError> {
+        Ok(Self(self.0.floor()))
+    }
+
+    fn ceil(&self) -> Result<Self, EvalError> {
+        Ok(Self(self.0.ceil()))
+    }
+
+    fn round(&self) -> Result<Self, EvalError> {
+        Ok(Self(self.0.round()))
+    }
+
+    fn compare(&self, rhs: &Self) -> Option<Ordering> {
+        Some(self.cmp(rhs))
+    }
+
+    fn format(&self, format: &NumberFormat) -> String {
+        match format.fraction {
+            FractionStyle::Improper => self.to_string(),
+            FractionStyle::Mixed => self.to_mixed_string(),
+        }
+    }
+
+    /// Rounded half away from zero.
+    fn to_fixed(&self, decimals: u32) -> String {
+        let scale = BigRational::from_integer(Pow::pow(BigInt::from(10), decimals));
+        let digits = (&self.0 * scale).round().to_integer();
+        BigDecimal::new(digits, i64::from(decimals)).to_plain_string()
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.denom().is_one() {
+            write!(f, "{}", self.0.numer())
+        } else {
+            write!(f, "{}/{}", self.0.numer(), self.0.denom())
+        }
+    }
+}
+
+fn exact_root(n: &BigInt, k: u32) -> Option<BigInt> {
+    let root = n.nth_root(k);
+    (Pow::pow(&root, k) == *n).then_some(root)
+}
@@ -0,0 +1,190 @@
+use std::{collections::HashMap, rc::Rc};
+
+use super::{
+    builtins,
+    error::EvalError,
+    function::{Arity, Callable, NativeFunction},
+    number::{Number, Numeric},
+};
+
+/// Where an operator stands relative to its operands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fixity {
+    /// Before its operand, like `-x`
+    Prefix,
+    /// Between its operands, like `a + b`
+    Infix,
+    /// After its operand, like `n!`
+    Postfix,
+}
+
+/// How a chain of infix operators of the same precedence groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    /// `1-2-3` is `(1-2)-3`
+    Left,
+    /// `2^3^2` is `2^(3^2)`
+    Right,
+}
+
+/// How an operator is parsed.
+///
+/// Infix operators of a higher precedence bind tighter, `to` and `in` have precedence 1.
+/// The operand of a prefix operator extends over the infix operators of at least its precedence,
+/// so `-` with the precedence of `^` reads `-2^2` as `-(2^2)`. Postfix operators bind tightest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Syntax {
+    pub prec: i8,
+    pub assoc: Assoc,
+}
+
+impl Syntax {
+    pub const fn new(prec: i8, assoc: Assoc) -> Self {
+        Self { prec, assoc }
+    }
+}
+
+/// The operators a parser knows, without what they compute.
+#[derive(Debug, Clone, Default)]
+pub struct Grammar {
+    operators: HashMap<(Fixity, String), Syntax>,
+}
+
+thread_local! {
+    static BUILTIN_GRAMMAR: Rc<Grammar> = OperatorTable::<f64>::with_builtins().grammar();
+}
+
+impl Grammar {
+    /// The grammar of the operators of [`OperatorTable::with_builtins`].
+    pub fn builtin() -> Rc<Self> {
+        BUILTIN_GRAMMAR.with(Rc::clone)
+    }
+
+    pub fn get(&self, symbol: &str, fixity: Fixity) -> Option<Syntax> {
+        self.operators.get(&(fixity, String::from(symbol))).copied()
+    }
+
+    /// Every symbol with any fixity.
+    pub fn symbols(&self) -> impl Iterator<Item = &str> {
+        self.operators.keys().map(|(_, symbol)| symbol.as_str())
+    }
+}
+
+/// An operator as registered in an [`OperatorTable`].
+pub struct Operator<N: Numeric = Number> {
+    syntax: Syntax,
+    callable: Rc<dyn Callable<N>>,
+}
+
+impl<N: Numeric> Operator<N> {
+    pub fn syntax(&self) -> Syntax {
+        self.syntax
+    }
+
+    /// The implementation, taking one operand or two for infix operators.
+    pub fn callable(&self) -> &dyn Callable<N> {
+        self.callable.as_ref()
+    }
+}
+
+/// The operators an expression can use, looked up by symbol and fixity.
+///
+/// Hosts extend it at runtime, say with `//` for integer division, and hand its
+/// [`OperatorTable::grammar`] to the parser so expressions can use the new symbols.
+#[derive(Clone)]
+pub struct OperatorTable<N: Numeric = Number> {
+    grammar: Rc<Grammar>,
+    operators: HashMap<(Fixity, String), Rc<Operator<N>>>,
+}
+
+impl<N: Numeric> Default for OperatorTable<N> {
+    fn default() -> Self {
+        Self {
+            grammar: Rc::default(),
+            operators: HashMap::new(),
+        }
+    }
+}
+
+impl<N: Numeric> std::fmt::Debug for OperatorTable<N> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.grammar.operators.keys()).finish()
+    }
+}
+
+impl<N: Numeric> OperatorTable<N> {
+    /// A table without any operators.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A table holding the arithmetic operators from [`builtins`].
+    pub fn with_builtins() -> Self {
+        let mut table = Self::new();
+        builtins::register_operators(&mut table);
+        table
+    }
+
+    /// Register `callable` as the operator `symbol`, returning the operator it replaces.
+    pub fn register<C: Callable<N> + 'static>(
+        &mut self,
+        symbol: &str,
+        fixity: Fixity,
+        syntax: Syntax,
+        callable: C,
+    ) -> Option<Rc<Operator<N>>> {
+        let key = (fixity, String::from(symbol));
+        Rc::make_mut(&mut self.grammar).operators.insert(key.clone(), syntax);
+        let callable = Rc::new(callable);
+        self.operators.insert(key, Rc::new(Operator { syntax, callable }))
+    }
+
+    /// Register an infix operator computing `func(lhs, rhs)`.
+    pub fn register_infix<F>(
+        &mut self,
+        symbol: &str,
+        prec: i8,
+        assoc: Assoc,
+        doc: &str,
+        func: F,
+    ) -> Option<Rc<Operator<N>>>
+    where
+        F: Fn(&N, &N) -> Result<N, EvalError> + 'static,
+    {
+        let callable = NativeFunction::new(symbol, Arity::exact(2), doc, move |args: &[N]| func(&args[0], &args[1]));
+        self.register(symbol, Fixity::Infix, Syntax::new(prec, assoc), callable)
+    }
+
+    /// Register a prefix operator whose operand extends over the infix operators of at least `prec`.
+    pub fn register_prefix<F>(&mut self, symbol: &str, prec: i8, doc: &str, func: F) -> Option<Rc<Operator<N>>>
+    where
+        F: Fn(&N) -> Result<N, EvalError> + 'static,
+    {
+        let callable = NativeFunction::new(symbol, Arity::exact(1), doc, move |args: &[N]| func(&args[0]));
+        self.register(symbol, Fixity::Prefix, Syntax::new(prec, Assoc::Right), callable)
+    }
+
+    /// Register a postfix operator, which binds tighter than any other.
+    pub fn register_postfix<F>(&mut self, symbol: &str, doc: &str, func: F) -> Option<Rc<Operator<N>>>
+    where
+        F: Fn(&N) -> Result<N, EvalError> + 'static,
+    {
+        let callable = NativeFunction::new(symbol, Arity::exact(1), doc, move |args: &[N]| func(&args[0]));
+        self.register(symbol, Fixity::Postfix, Syntax::new(i8::MAX, Assoc::Left), callable)
+    }
+
+    pub fn unregister(&mut self, symbol: &str, fixity: Fixity) -> Option<Rc<Operator<N>>> {
+        let key = (fixity, String::from(symbol));
+        Rc::make_mut(&mut self.grammar).operators.remove(&key);
+        self.operators.remove(&key)
+    }
+
+    pub fn get(&self, symbol: &str, fixity: Fixity) -> Option<Rc<Operator<N>>> {
+        self.operators.get(&(fixity, String::from(symbol))).cloned()
+    }
+
+    /// How the operators of this table are parsed, to hand to a parser.
+    pub fn grammar(&self) -> Rc<Grammar> {
+        Rc::clone(&self.grammar)
+    }
+}
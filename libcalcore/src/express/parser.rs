@@ -1,12 +1,51 @@
 pub mod lookahead;
 
-use std::fmt::Display;
+use std::{fmt::Display, ops::Index};
 
+use bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive};
+
+use self::lookahead::{Expr, LookAhead, Program};
 use super::{
-    error::{CalculateError, ParserError},
+    error::{CalError, CalculateError, ParserError},
     lexer::Token,
+    operator::Grammar,
+    span::Span,
 };
 
+/// The syntax tree of an expression, whichever entry point parsed it.
+pub type Ast = lookahead::Expr;
+
+/// Parse all of `src` as one expression with the built-in operators.
+///
+/// Unlike [`LookAhead::parse_expr`], which stops before the first token that cannot continue
+/// the expression, this fails on anything left over, so `1 2` is an error rather than `1`.
+pub fn parse(src: &str) -> Result<Ast, CalError> {
+    parse_all(LookAhead::try_from(src)?)
+}
+
+/// Parse `src` as a program, going on after syntax errors to report all of them at once.
+///
+/// The program has an [`Expr::Error`](lookahead::Expr::Error) in place of every statement with
+/// an error, so evaluating it fails, while the statements without errors still run.
+pub fn parse_recovering(src: &str) -> (Program, Vec<CalError>) {
+    let mut parser = LookAhead::recovering(src.chars(), Grammar::builtin());
+    let program = parser.parse_program();
+    let mut errors = parser.take_errors();
+    let program = program.unwrap_or_else(|err| {
+        errors.push(err);
+        Program::default()
+    });
+    (program, errors)
+}
+
+fn parse_all<I: Iterator<Item = char>>(mut parser: LookAhead<I>) -> Result<Ast, CalError> {
+    let ast = parser.parse_expr()?;
+    match parser.peek() {
+        Token::EOF => Ok(ast),
+        token => CalError::syn(format!("Expect {{operator}}, get '{token:?}'").as_str(), parser.peek_span()),
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub enum NodeType {
     None,
@@ -48,10 +87,14 @@ impl From<Token> for OpSymbol {
     }
 }
 
+/// The index of a [`Node`] in its [`Tree`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+
 #[derive(Debug, Clone)]
 pub struct Node {
     pub node_type: NodeType,
-    pub children: Vec<Node>,
+    pub children: Vec<NodeId>,
 }
 
 impl Display for Node {
@@ -67,6 +110,12 @@ impl Display for Node {
     }
 }
 
+impl Default for Node {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Node {
     pub fn new() -> Self {
         Self {
@@ -85,275 +134,317 @@ impl Node {
         Self::new_type(NodeType::Token(token))
     }
 
-    pub fn calculate(&self) -> Result<f64, CalculateError> {
-        match &self.node_type {
-            NodeType::Expr => self.children[0].calculate(),
-            NodeType::UnionExpr => {
-                if self.children.len() == 2 {
-                    if let NodeType::UnionOp(ops) = &self.children[0].node_type {
-                        match ops {
-                            OpSymbol::Add => self.children[1].calculate(),
-                            OpSymbol::Subtract => Ok(-self.children[1].calculate()?),
-                            _ => Err(CalculateError),
-                        }
-                    } else {
-                        Err(CalculateError)
-                    }
-                } else {
-                    self.children[0].calculate()
-                }
-            }
-            NodeType::PhExpr => self.children[0].calculate(),
-            NodeType::AddExpr(add_ops) => {
-                if self.children.len() == 2 {
-                    match add_ops {
-                        OpSymbol::Add => {
-                            Ok(self.children[0].calculate()? + self.children[1].calculate()?)
-                        }
-                        OpSymbol::Subtract => {
-                            Ok(self.children[0].calculate()? - self.children[1].calculate()?)
-                        }
-                        _ => Err(CalculateError),
-                    }
-                } else {
-                    self.children[0].calculate()
-                }
-            }
-            NodeType::MulExpr(mul_ops) => {
-                if self.children.len() == 2 {
-                    match mul_ops {
-                        OpSymbol::Multiply => {
-                            Ok(self.children[0].calculate()? * self.children[1].calculate()?)
-                        }
-                        // todo: check the second num is valid for calculating
-                        OpSymbol::Divide => {
-                            Ok(self.children[0].calculate()? / self.children[1].calculate()?)
-                        }
-                        OpSymbol::Mod => {
-                            Ok(self.children[0].calculate()? % self.children[1].calculate()?)
-                        }
-                        _ => Err(CalculateError),
-                    }
-                } else {
-                    self.children[0].calculate()
-                }
-            }
-            NodeType::ExponExpr => {
-                if self.children.len() == 2 {
-                    Ok(self.children[0]
-                        .calculate()?
-                        .powf(self.children[1].calculate()?))
-                } else {
-                    self.children[0].calculate()
-                }
-            }
-            NodeType::Number(num) => Ok(*num),
-            _ => Err(CalculateError),
-        }
+    /// The value of this node in `tree`, calculating its children again for every node.
+    #[deprecated(note = "children are now ids into a `Tree`, calculate it as a whole with `Tree::calculate`")]
+    pub fn calculate(&self, tree: &Tree) -> Result<f64, CalculateError> {
+        self.value(tree, |i| tree[self.children[i]].calculate(tree))
+    }
+
+    /// The value of this node given `child(i)`, the value of its `i`th child.
+    fn value(
+        &self,
+        tree: &Tree,
+        child: impl Fn(usize) -> Result<f64, CalculateError>,
+    ) -> Result<f64, CalculateError> {
+        let value = match &self.node_type {
+            // an operator has no value of its own, its `UnionExpr` reads it
+            NodeType::UnionOp(_) => f64::NAN,
+            NodeType::UnionExpr if self.children.len() == 2 => match tree[self.children[0]].node_type {
+                NodeType::UnionOp(OpSymbol::Add) => child(1)?,
+                NodeType::UnionOp(OpSymbol::Subtract) => -child(1)?,
+                _ => return Err(CalculateError),
+            },
+            NodeType::AddExpr(add_ops) if self.children.len() == 2 => match add_ops {
+                OpSymbol::Add => child(0)? + child(1)?,
+                OpSymbol::Subtract => child(0)? - child(1)?,
+                _ => return Err(CalculateError),
+            },
+            NodeType::MulExpr(mul_ops) if self.children.len() == 2 => match mul_ops {
+                OpSymbol::Multiply => child(0)? * child(1)?,
+                // todo: check the second num is valid for calculating
+                OpSymbol::Divide => child(0)? / child(1)?,
+                OpSymbol::Mod => child(0)? % child(1)?,
+                _ => return Err(CalculateError),
+            },
+            NodeType::ExponExpr if self.children.len() == 2 => child(0)?.powf(child(1)?),
+            NodeType::Expr
+            | NodeType::UnionExpr
+            | NodeType::PhExpr
+            | NodeType::AddExpr(_)
+            | NodeType::MulExpr(_)
+            | NodeType::ExponExpr => child(0)?,
+            NodeType::Number(num) => *num,
+            _ => return Err(CalculateError),
+        };
+        Ok(value)
     }
 }
 
-#[derive(Debug)]
-pub struct AST {
-    root_node: Node,
+/// A syntax tree of the grammar [`AST`] reads, which groups some expressions differently from
+/// [`parse`], kept until the two are made to agree.
+///
+/// Its nodes live in one arena and refer to their children by [`NodeId`], each pushed after
+/// its children, so parsing and calculating take time linear in the number of tokens.
+#[derive(Debug, Clone)]
+pub struct Tree {
+    nodes: Vec<Node>,
+    root: NodeId,
 }
 
-impl AST {
-    /// The action ofNodeError parsing token is a process, which can be described by BNF:
+impl Tree {
+    /// The action of parsing token is a process, which can be described by BNF:
     /// ```BNF
     /// Expr ::= AddExpr;
     /// AddExpr ::= MulExpr {("+"|"-") AddExpr};
     /// MulExpr ::= ExponExpr {("*"|"/"|"%") ExponExpr};
-    /// ExponExpr ::= UnionExpr | UnionExpr "^" UnionExpr;
+    /// ExponExpr ::= UnionExpr ["^" UnionExpr];
     /// UnionExpr ::= PhExpr | UnionOp UnionExpr;
     /// UnionOp ::= "+" | "-";
     /// PhExpr ::= "(" AddExpr ")" | NUMBER;
     /// ```
+    ///
+    /// Parsing stops at the first token that does not fit, ignoring the rest.
+    pub fn parse(tokens: Vec<Token>) -> Result<Tree, ParserError> {
+        let mut parser = TreeParser { tokens, pos: 0, nodes: vec![] };
+        let root = parser.parse_expr()?;
+        Ok(Self { nodes: parser.nodes, root })
+    }
+
+    pub fn root(&self) -> NodeId {
+        self.root
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn calculate(&self) -> Result<f64, CalculateError> {
+        // children come before their parents, so one pass in order sees every operand computed
+        let mut values: Vec<f64> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let value = node.value(self, |i| Ok(values[node.children[i].0]))?;
+            values.push(value);
+        }
+        Ok(values[self.root.0])
+    }
+
+    /// The same expression as an [`Ast`], grouped as this tree groups it, which evaluates to what
+    /// [`Tree::calculate`] does.
+    ///
+    /// Its spans are empty, as the tokens it was parsed from have no positions.
+    pub fn to_ast(&self) -> Ast {
+        let span = Span::default();
+        let mut exprs: Vec<Option<Ast>> = Vec::with_capacity(self.nodes.len());
+        for node in &self.nodes {
+            let mut child = |i: usize| exprs[node.children[i].0].take().unwrap_or(Expr::Error(span));
+            let expr = match &node.node_type {
+                // an operator has no expression of its own, its `UnionExpr` reads it
+                NodeType::UnionOp(_) => None,
+                NodeType::UnionExpr if node.children.len() == 2 => match self[node.children[0]].node_type {
+                    NodeType::UnionOp(OpSymbol::Add) => Some(Expr::prefix("+", child(1), span)),
+                    NodeType::UnionOp(OpSymbol::Subtract) => Some(Expr::prefix("-", child(1), span)),
+                    _ => Some(Expr::Error(span)),
+                },
+                NodeType::AddExpr(add_ops) if node.children.len() == 2 => match add_ops {
+                    OpSymbol::Add => Some(Expr::binary("+", child(0), child(1), span)),
+                    OpSymbol::Subtract => Some(Expr::binary("-", child(0), child(1), span)),
+                    _ => Some(Expr::Error(span)),
+                },
+                NodeType::MulExpr(mul_ops) if node.children.len() == 2 => match mul_ops {
+                    OpSymbol::Multiply => Some(Expr::binary("*", child(0), child(1), span)),
+                    OpSymbol::Divide => Some(Expr::binary("/", child(0), child(1), span)),
+                    OpSymbol::Mod => Some(Expr::binary("%", child(0), child(1), span)),
+                    _ => Some(Expr::Error(span)),
+                },
+                NodeType::ExponExpr if node.children.len() == 2 => Some(Expr::binary("^", child(0), child(1), span)),
+                NodeType::Expr
+                | NodeType::UnionExpr
+                | NodeType::PhExpr
+                | NodeType::AddExpr(_)
+                | NodeType::MulExpr(_)
+                | NodeType::ExponExpr => Some(child(0)),
+                // a literal too large for `f64` is calculated as infinity, and `1e309` evaluates to it as well
+                NodeType::Number(num) if num.is_infinite() => Some(Expr::Num(BigDecimal::new(1.into(), -309), span)),
+                NodeType::Number(num) => {
+                    Some(BigDecimal::from_f64(*num).map_or(Expr::Error(span), |num| Expr::Num(num, span)))
+                }
+                _ => Some(Expr::Error(span)),
+            };
+            exprs.push(expr);
+        }
+        exprs[self.root.0].take().unwrap_or(Expr::Error(span))
+    }
+}
+
+impl Index<NodeId> for Tree {
+    type Output = Node;
+
+    fn index(&self, index: NodeId) -> &Self::Output {
+        &self.nodes[index.0]
+    }
+}
+
+/// The entry point from before [`parse`], evaluating to `f64` rounded to 8 decimal places.
+///
+/// It still reads its tokens with the grammar of [`Tree`], so its answers stay as they were even
+/// where [`parse`] groups differently, as `1-2-3` is `2` here and `-4` there. The expression is
+/// then held and evaluated as an [`Ast`] like any other.
+#[derive(Debug)]
+pub struct AST {
+    root: Ast,
+}
+
+impl AST {
+    /// Parse `tokens` from [`tokenize`](super::lexer::tokenize) as [`Tree::parse`] does.
     pub fn parse(tokens: Vec<Token>) -> Result<AST, ParserError> {
-        let mut nodes = tokens.into_iter().map(Node::from_token).collect();
-        Ok(Self { root_node: parse_expr(&mut nodes)? })
+        Ok(Self { root: Tree::parse(tokens)?.to_ast() })
     }
 
     pub fn eval(&self) -> Result<f64, CalculateError> {
         // keep the precision
-        self.root_node.calculate().map(|mut num| {
-            let percision = 10_i32.pow(8) as f64;
-            num = (num * percision).round() / percision;
-            num
-        })
+        self.root.eval().map(round).map_err(|_| CalculateError)
+    }
+
+    pub fn root(&self) -> &Ast {
+        &self.root
     }
 }
 
 impl Display for AST {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.root_node)
+        write!(f, "{:?}", self.root)
     }
 }
 
+fn round(num: f64) -> f64 {
+    let percision = 10_i32.pow(8) as f64;
+    (num * percision).round() / percision
+}
+
 // ------- Parser ---------
 
-/// Expr ::= AddExpr;
-fn parse_expr(nodes: &mut Vec<Node>) -> Result<Node, ParserError> {
-    // this line is the part `AddExpr` of `Expr ::= AddExpr;`
-    parse_add_expr(nodes)?;
-    // back the first node
-    Ok(nodes[0].clone())
+/// Reads tokens in order, pushing each node to the arena once its children are in it.
+struct TreeParser {
+    tokens: Vec<Token>,
+    pos: usize,
+    nodes: Vec<Node>,
 }
 
-/// AddExpr ::= MulExpr {("+"|"-") AddExpr};
-fn parse_add_expr(nodes: &mut Vec<Node>) -> Result<(), ParserError> {
-    parse_mul_expr(nodes)?;
-    let mut add_node = Node::new_type(NodeType::AddExpr(OpSymbol::Unknown));
-    add_node.children.push(nodes[0].clone());
-    // check if has symbol that is "+", "-"
-    if let Some(node) = nodes.get(1) {
-        if let NodeType::Token(token) = &node.node_type {
-            match token {
-                Token::Plus | Token::Minus => {
-                    if let Token::Plus = token {
-                        add_node.node_type = NodeType::AddExpr(OpSymbol::Add);
-                    } else if let Token::Minus = token {
-                        add_node.node_type = NodeType::AddExpr(OpSymbol::Subtract);
-                    }
-                    // remove symbol
-                    nodes.drain(..=1);
-                    // parse next node
-                    parse_add_expr(nodes)?;
-                    add_node.children.push(nodes[0].clone());
-                }
-                _ => {}
-            }
+impl TreeParser {
+    fn push(&mut self, node_type: NodeType, children: Vec<NodeId>) -> NodeId {
+        self.nodes.push(Node { node_type, children });
+        NodeId(self.nodes.len() - 1)
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    /// Expr ::= AddExpr;
+    fn parse_expr(&mut self) -> Result<NodeId, ParserError> {
+        self.parse_add_expr()
+    }
+
+    /// AddExpr ::= MulExpr {("+"|"-") AddExpr};
+    ///
+    /// The terms are read in a loop and then grouped from the right as the recursion would.
+    fn parse_add_expr(&mut self) -> Result<NodeId, ParserError> {
+        let mut terms = vec![self.parse_mul_expr()?];
+        let mut ops = vec![];
+        while let Some(op @ (Token::Plus | Token::Minus)) = self.peek() {
+            ops.push(match op {
+                Token::Plus => OpSymbol::Add,
+                _ => OpSymbol::Subtract,
+            });
+            self.pos += 1;
+            terms.push(self.parse_mul_expr()?);
+        }
+        let last = terms.pop().expect("read at least one term");
+        let mut add_node = self.push(NodeType::AddExpr(OpSymbol::Unknown), vec![last]);
+        while let (Some(term), Some(op)) = (terms.pop(), ops.pop()) {
+            add_node = self.push(NodeType::AddExpr(op), vec![term, add_node]);
         }
+        Ok(add_node)
     }
-    nodes[0] = add_node;
-    return Ok(());
-}
 
-/// MulExpr ::= ExponExpr {("*"|"/"|"%") ExponExpr};
-fn parse_mul_expr(nodes: &mut Vec<Node>) -> Result<(), ParserError> {
-    parse_expon_expr(nodes)?;
-    let mut mul_node = Node::new_type(NodeType::MulExpr(OpSymbol::Unknown));
-    mul_node.children.push(nodes[0].clone());
-    nodes[0] = mul_node.clone();
-    // check if has symbol that is "*", "/", "%"
-    while let Some(node) = nodes.get(1) {
-        if let NodeType::Token(token) = &node.node_type {
-            match token {
-                Token::Multiply | Token::Division | Token::Percent => {
-                    if let Token::Multiply = token {
-                        mul_node.node_type = NodeType::MulExpr(OpSymbol::Multiply);
-                    } else if let Token::Division = token {
-                        mul_node.node_type = NodeType::MulExpr(OpSymbol::Divide);
-                    } else if let Token::Percent = token {
-                        mul_node.node_type = NodeType::MulExpr(OpSymbol::Mod);
-                    }
-                    // remove symbol
-                    nodes.drain(..=1);
-                    // parse next node
-                    parse_expon_expr(nodes)?;
-                    mul_node.children.push(nodes[0].clone());
-                    // put node in to nodes
-                    nodes[0] = mul_node.clone();
-                    // pack node
-                    mul_node = Node::new_type(NodeType::MulExpr(OpSymbol::Unknown));
-                    mul_node.children.push(nodes[0].clone());
-                }
+    /// MulExpr ::= ExponExpr {("*"|"/"|"%") ExponExpr};
+    fn parse_mul_expr(&mut self) -> Result<NodeId, ParserError> {
+        let first = self.parse_expon_expr()?;
+        let mut mul_node = None;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Multiply) => OpSymbol::Multiply,
+                Some(Token::Division) => OpSymbol::Divide,
+                Some(Token::Percent) => OpSymbol::Mod,
                 _ => break,
-            }
+            };
+            self.pos += 1;
+            let rhs = self.parse_expon_expr()?;
+            mul_node = Some(self.push(NodeType::MulExpr(op), vec![mul_node.unwrap_or(first), rhs]));
+        }
+        match mul_node {
+            Some(mul_node) => Ok(mul_node),
+            None => Ok(self.push(NodeType::MulExpr(OpSymbol::Unknown), vec![first])),
         }
     }
-    Ok(())
-}
 
-/// ExponExpr ::= UnionExpr {"^" PhExpr};
-fn parse_expon_expr(nodes: &mut Vec<Node>) -> Result<(), ParserError> {
-    parse_union_expr(nodes)?;
-    let mut expon_node = Node::new_type(NodeType::ExponExpr);
-    expon_node.children.push(nodes[0].clone());
-
-    if let Some(node) = nodes.get(1) {
-        if let NodeType::Token(Token::Exponential) = &node.node_type {
-            // remove symbol
-            nodes.drain(..=1);
-            // parse next node
-            parse_union_expr(nodes)?;
-            expon_node.children.push(nodes[0].clone());
+    /// ExponExpr ::= UnionExpr ["^" UnionExpr];
+    ///
+    /// At most one `^`, so in `2^3^2` the second is left over.
+    fn parse_expon_expr(&mut self) -> Result<NodeId, ParserError> {
+        let mut children = vec![self.parse_union_expr()?];
+        if let Some(Token::Exponential) = self.peek() {
+            self.pos += 1;
+            children.push(self.parse_union_expr()?);
         }
+        Ok(self.push(NodeType::ExponExpr, children))
     }
-    nodes[0] = expon_node;
-    Ok(())
-}
-
-/// UnionExpr ::= PhExpr | UnionOp UnionExpr;
-fn parse_union_expr(nodes: &mut Vec<Node>) -> Result<(), ParserError> {
-    match &nodes[0].node_type {
-        NodeType::Token(token) => {
-            let mut union_node = Node {
-                node_type: NodeType::UnionExpr,
-                children: vec![],
-            };
 
-            match token {
-                Token::OpenPh | Token::Number(_) => parse_ph_expr(nodes)?,
-                Token::Plus | Token::Minus => {
-                    if let Token::Plus = token {
-                        union_node
-                            .children
-                            .push(Node::new_type(NodeType::UnionOp(OpSymbol::Add)))
-                    } else if let Token::Minus = token {
-                        union_node
-                            .children
-                            .push(Node::new_type(NodeType::UnionOp(OpSymbol::Subtract)))
-                    }
-                    // remove the symbol
-                    nodes.remove(0);
-                    parse_union_expr(nodes)?;
-                }
-                _ => return Err(ParserError::UnionExpr(1)),
+    /// UnionExpr ::= PhExpr | UnionOp UnionExpr;
+    fn parse_union_expr(&mut self) -> Result<NodeId, ParserError> {
+        let children = match self.peek() {
+            Some(Token::OpenPh | Token::Number(_)) => vec![self.parse_ph_expr()?],
+            Some(op @ (Token::Plus | Token::Minus)) => {
+                let op = match op {
+                    Token::Plus => OpSymbol::Add,
+                    _ => OpSymbol::Subtract,
+                };
+                self.pos += 1;
+                let op = self.push(NodeType::UnionOp(op), vec![]);
+                vec![op, self.parse_union_expr()?]
             }
-
-            // set child
-            union_node.children.push(nodes[0].clone());
-            // put current node on nodes[0]
-            nodes[0] = union_node;
-            Ok(())
-        }
-        _ => Err(ParserError::UnionExpr(2)),
+            Some(_) => return Err(ParserError::UnionExpr(1)),
+            None => return Err(ParserError::UnionExpr(2)),
+        };
+        Ok(self.push(NodeType::UnionExpr, children))
     }
-}
 
-/// PhExpr ::= "(" AddExpr ")" | NUMBER;
-fn parse_ph_expr(nodes: &mut Vec<Node>) -> Result<(), ParserError> {
-    match nodes[0].node_type {
-        NodeType::Token(Token::OpenPh) => {
-            // remove the first "("
-            nodes.remove(0);
-            // the expression between "(" and ")"
-            parse_add_expr(nodes)?;
-            // check if the second node is the symbol ")"
-            if let Some(node) = nodes.get(1) {
-                if let NodeType::Token(Token::ClosePh) = node.node_type {
-                    nodes.remove(1);
-
-                    let add_node = nodes[0].clone();
-
-                    // create `PhExpr` node and replace nodes[0]
-                    nodes[0] = Node {
-                        node_type: NodeType::PhExpr,
-                        children: vec![add_node],
-                    };
+    /// PhExpr ::= "(" AddExpr ")" | NUMBER;
+    fn parse_ph_expr(&mut self) -> Result<NodeId, ParserError> {
+        match self.peek() {
+            Some(Token::OpenPh) => {
+                self.pos += 1;
+                // the expression between "(" and ")"
+                let add_node = self.parse_add_expr()?;
+                match self.peek() {
+                    Some(Token::ClosePh) => {
+                        self.pos += 1;
+                        Ok(self.push(NodeType::PhExpr, vec![add_node]))
+                    }
+                    Some(_) => Ok(add_node),
+                    None => Err(ParserError::PhExpr),
                 }
-            } else {
-                return Err(ParserError::PhExpr);
             }
-        }
-        NodeType::Token(Token::Number(fnum)) => {
-            nodes[0] = Node {
-                node_type: NodeType::Number(fnum),
-                children: vec![],
+            Some(Token::Number(fnum)) => {
+                let num = fnum.to_f64().unwrap_or(f64::NAN);
+                self.pos += 1;
+                Ok(self.push(NodeType::Number(num), vec![]))
             }
+            _ => Err(ParserError::PhExpr),
         }
-        _ => return Err(ParserError::PhExpr),
     }
-    Ok(())
-}
\ No newline at end of file
+}
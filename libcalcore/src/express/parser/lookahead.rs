@@ -1,69 +1,427 @@
-use std::{mem, str::Chars};
+use std::{fmt::Display, mem, rc::Rc, str::Chars};
+
+use bigdecimal::{BigDecimal, ToPrimitive};
 
 use crate::express::{
-    error::CalError,
+    env::{Environment, Function, Scope},
+    error::{CalError, EvalError},
+    function::Arity,
     lexer::{Lexer, Token},
+    number::{Measures, Numeric},
+    operator::{Assoc, Fixity, Grammar, Syntax},
+    span::{Span, Spanned},
+    unit::Unit,
 };
 
-use super::OpSymbol;
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Expr {
-    Num(f64),
+    Num(BigDecimal, Span),
+    /// An imaginary literal like `2i`
+    Imag(BigDecimal, Span),
+    /// A date literal like `2026-10-18` or `2026-10-18 17:30`, as seconds since 1970-01-01
+    Date(i64, Span),
+    /// A time literal like `17:30`, as seconds
+    Clock(i64, Span),
+    Var(String, Span),
+    Call(Box<Call>),
     UnaryOp(Box<UnaryOp>),
     BinOp(Box<BinOp>),
+    /// A number with a unit suffix, like `3 km`
+    WithUnit(Box<WithUnit>),
+    /// `expr to unit` or `expr in unit`
+    Convert(Box<Convert>),
+    /// What a recovering parser puts where it found a syntax error
+    Error(Span),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+pub struct Call {
+    name: String,
+    args: Vec<Expr>,
+    span: Span,
+}
+
+/// A prefix or postfix operator, by its symbol in the environment's operator table.
+#[derive(Debug, Clone)]
 pub struct UnaryOp {
-    op: OpSymbol,
+    op: String,
+    fixity: Fixity,
     num: Expr,
+    span: Span,
 }
 
-#[derive(Debug)]
+/// An infix operator, by its symbol in the environment's operator table.
+#[derive(Debug, Clone)]
 pub struct BinOp {
-    op: OpSymbol,
+    op: String,
     lhs: Expr,
     rhs: Expr,
+    span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct WithUnit {
+    expr: Expr,
+    unit: UnitExpr,
+    /// Whether a bound variable of the same name scales instead, as the `x` of `2x`
+    scales: bool,
+    span: Span,
+}
+
+#[derive(Debug, Clone)]
+pub struct Convert {
+    expr: Expr,
+    unit: UnitExpr,
+    span: Span,
+}
+
+/// A product of units with integer exponents, like `m/s^2` or `kg*m`.
+#[derive(Debug, Clone)]
+pub struct UnitExpr {
+    /// The name of each unit and its exponent, negated for units after a `/`
+    factors: Vec<(String, i32)>,
+    span: Span,
+}
+
+/// A single line or `;`-separated part of a program.
+#[derive(Debug)]
+pub enum Stmt {
+    Let(Box<Let>),
+    Def(Box<Def>),
+    Expr(Expr),
+}
+
+/// `let name = expr`, or just `name = expr`
+#[derive(Debug)]
+pub struct Let {
+    name: String,
+    expr: Expr,
+    span: Span,
+}
+
+/// `name(param, ...) = body`, defining a function for later statements
+#[derive(Debug)]
+pub struct Def {
+    name: String,
+    params: Vec<String>,
+    body: Expr,
+    span: Span,
+}
+
+/// A sequence of statements sharing one [`Environment`].
+#[derive(Debug, Default)]
+pub struct Program {
+    stmts: Vec<Stmt>,
+}
+
+/// How adjacent operands like `2x`, `2(3+4)` or `(a)(b)` multiply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Implicit {
+    /// Tighter than `*` and `/` but looser than `^`, so `1/2x` is `1/(2x)` and `2^3(4)` is `(2^3)*4`
+    #[default]
+    Tight,
+    /// Like `*`, so `1/2x` is `(1/2)*x` and `1/2 km` is half a kilometre
+    Loose,
+    /// Adjacent operands are a syntax error and a name after a number is always its unit
+    Strict,
+}
+
+/// What `%` means.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Percent {
+    /// The remainder of a division, as in `7 % 3`
+    #[default]
+    Modulo,
+    /// Hundredths as on a pocket calculator, so `50%` is `0.5` and `200 + 10%` is `220`
+    Calculator,
 }
 
 pub struct LookAhead<I: Iterator<Item = char>> {
-    peek: Token,
+    peek: Spanned<Token>,
+    /// The span of the token popped last
+    last: Span,
     lexer: Lexer<I>,
+    grammar: Rc<Grammar>,
+    implicit: Implicit,
+    percent: Percent,
+    /// The errors noted so far in recovering mode, or `None` to fail on the first one
+    errors: Option<Vec<CalError>>,
 }
 
-fn get_tok_prec(token: &Token) -> i8 {
-    match token {
-        Token::Plus | Token::Minus => 5,
-        Token::Multiply | Token::Division | Token::Percent => 10,
-        Token::Exponential => 15,
-        _ => -1,
-    }
-}
+/// The precedence of `to` and `in`, which convert the whole expression before them,
+/// so `1 km + 1 m to cm` is `(1 km + 1 m) to cm`.
+const CONVERT_PREC: i8 = 1;
+
+/// The precedence of adjacent operands in [`Implicit::Tight`] mode, between `*` and `^`.
+const TIGHT_PREC: i8 = 12;
 
 impl Expr {
-    pub fn eval(&self) -> Result<f64, ()> {
+    /// The span of source this expression was parsed from.
+    pub fn span(&self) -> Span {
         match self {
-            Expr::Num(n) => Ok(*n),
-            Expr::UnaryOp(uo) => match uo.op {
-                OpSymbol::Add => uo.num.eval(),
-                OpSymbol::Subtract => Ok(-uo.num.eval()?),
-                _ => Err(()),
+            Expr::Num(_, span) | Expr::Imag(_, span) | Expr::Var(_, span) => *span,
+            Expr::Date(_, span) | Expr::Clock(_, span) | Expr::Error(span) => *span,
+            Expr::Call(call) => call.span,
+            Expr::UnaryOp(uo) => uo.span,
+            Expr::BinOp(bo) => bo.span,
+            Expr::WithUnit(wu) => wu.span,
+            Expr::Convert(conv) => conv.span,
+        }
+    }
+
+    /// `lhs op rhs` with the infix operator `op` of the environment.
+    pub fn binary(op: &str, lhs: Expr, rhs: Expr, span: Span) -> Expr {
+        Expr::BinOp(Box::new(BinOp { op: String::from(op), lhs, rhs, span }))
+    }
+
+    /// `op num` with the prefix operator `op` of the environment.
+    pub fn prefix(op: &str, num: Expr, span: Span) -> Expr {
+        Expr::UnaryOp(Box::new(UnaryOp { op: String::from(op), fixity: Fixity::Prefix, num, span }))
+    }
+
+    /// Evaluate as `f64` in a fresh [`Environment`], where only the predefined constants are bound.
+    pub fn eval(&self) -> Result<f64, CalError> {
+        self.eval_with(&Environment::<f64>::with_builtins())
+    }
+
+    /// Evaluate in `env`, computing with whatever [`Numeric`] type the environment holds.
+    pub fn eval_with<N: Numeric>(&self, env: &Environment<N>) -> Result<N, CalError> {
+        self.eval_in(&Scope::global(env))
+    }
+
+    fn eval_in<N: Numeric>(&self, scope: &Scope<N>) -> Result<N, CalError> {
+        let mode = scope.env().mode();
+        let value = match self {
+            Expr::Num(n, _) => N::from_literal(n, mode),
+            Expr::Imag(n, _) => N::from_imaginary(n, mode),
+            Expr::Date(seconds, _) => measures::<N>().and_then(|measures| (measures.from_date)(*seconds, mode)),
+            Expr::Clock(seconds, _) => measures::<N>().and_then(|measures| (measures.from_clock)(*seconds, mode)),
+            Expr::Var(name, _) => match scope.get(name) {
+                Some(value) => value,
+                // the imaginary unit, unless something binds `i`, in the modes that have one
+                None if name == "i" => N::from_imaginary(&BigDecimal::from(1), mode)
+                    .map_err(|_| EvalError::UnboundVariable(name.clone())),
+                // a unit on its own, as in the `s` of `20 m / s`
+                None => match scope.env().unit(name) {
+                    Ok(unit) => measures::<N>()
+                        .and_then(|measures| {
+                            (measures.with_unit)(&N::from_literal(&BigDecimal::from(1), mode)?, &unit)
+                        })
+                        .map_err(|_| EvalError::UnboundVariable(name.clone())),
+                    // only a representation with units has currencies to miss a rate for
+                    Err(EvalError::MissingRate(code)) if N::measures().is_some() => Err(EvalError::MissingRate(code)),
+                    Err(_) => Err(EvalError::UnboundVariable(name.clone())),
+                },
             },
-            Expr::BinOp(bo) => {
-                let lhv = bo.lhs.eval()?;
-                let rhv = bo.rhs.eval()?;
-                match bo.op {
-                    OpSymbol::Add => Ok(lhv + rhv),
-                    OpSymbol::Subtract => Ok(lhv - rhv),
-                    OpSymbol::Multiply => Ok(lhv * rhv),
-                    OpSymbol::Divide => Ok(lhv / rhv),
-                    OpSymbol::Mod => Ok(lhv % rhv),
-                    OpSymbol::Caret => Ok(lhv.powf(rhv)),
-                    _ => Err(()),
+            Expr::Call(call) => return call.eval_in(scope),
+            Expr::UnaryOp(uo) => return uo.eval_in(scope),
+            Expr::BinOp(bo) => return bo.eval_in(scope),
+            Expr::WithUnit(wu) => return wu.eval_in(scope),
+            Expr::Error(span) => return CalError::syn("Cannot evaluate a syntax error", *span),
+            Expr::Convert(conv) => {
+                let value = conv.expr.eval_in(scope)?;
+                let unit = conv.unit.resolve(|name| scope.env().unit(name))?;
+                measures::<N>().and_then(|measures| (measures.convert)(&value, &unit))
+            }
+        };
+        value.or_else(|ee| CalError::eval(ee, self.span()))
+    }
+}
+
+impl UnitExpr {
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// Look up every unit with `lookup` and multiply them together.
+    pub fn resolve(&self, lookup: impl Fn(&str) -> Result<Unit, EvalError>) -> Result<Unit, CalError> {
+        // a single unit keeps its rounding, so `to EUR` shows cents
+        if let [(name, 1)] = &self.factors[..] {
+            return lookup(name).or_else(|ee| CalError::eval(ee, self.span));
+        }
+        let mut product = Unit::one();
+        for (name, exp) in &self.factors {
+            product = lookup(name)
+                .and_then(|unit| product.mul(&unit.powi(*exp)?))
+                .or_else(|ee| CalError::eval(ee, self.span))?;
+        }
+        Ok(product.with_label(&self.to_string()))
+    }
+}
+
+/// The units as written, e.g. `m/s^2`.
+impl Display for UnitExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, (name, exp)) in self.factors.iter().enumerate() {
+            let exp = match i {
+                0 => *exp,
+                _ if *exp < 0 => {
+                    write!(f, "/")?;
+                    -exp
                 }
+                _ => {
+                    write!(f, "*")?;
+                    *exp
+                }
+            };
+            match exp {
+                1 => write!(f, "{name}")?,
+                _ => write!(f, "{name}^{exp}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How `N` does units and dates, or the error for representations without them.
+fn measures<N: Numeric>() -> Result<Measures<N>, EvalError> {
+    N::measures().ok_or(EvalError::UnitsUnsupported)
+}
+
+/// Apply the operator `symbol` of the environment to `args`.
+fn apply<N: Numeric>(scope: &Scope<N>, symbol: &str, fixity: Fixity, args: &[N]) -> Result<N, EvalError> {
+    match scope.env().operators().get(symbol, fixity) {
+        Some(operator) => operator.callable().call(args),
+        None => Err(EvalError::UnknownOperator(String::from(symbol))),
+    }
+}
+
+impl UnaryOp {
+    fn eval_in<N: Numeric>(&self, scope: &Scope<N>) -> Result<N, CalError> {
+        let value = self.num.eval_in(scope)?;
+        apply(scope, &self.op, self.fixity, &[value]).or_else(|ee| CalError::eval(ee, self.span))
+    }
+
+    fn is_percent(&self) -> bool {
+        self.fixity == Fixity::Postfix && self.op == "%"
+    }
+}
+
+impl BinOp {
+    fn eval_in<N: Numeric>(&self, scope: &Scope<N>) -> Result<N, CalError> {
+        let lhv = self.lhs.eval_in(scope)?;
+        let mut rhv = self.rhs.eval_in(scope)?;
+        // `200 + 10%` adds ten percent of 200
+        if let Expr::UnaryOp(uo) = &self.rhs {
+            if uo.is_percent() && matches!(self.op.as_str(), "+" | "-") {
+                rhv = lhv.mul(&rhv).or_else(|ee| CalError::eval(ee, uo.span))?;
+            }
+        }
+        apply(scope, &self.op, Fixity::Infix, &[lhv, rhv]).or_else(|ee| CalError::eval(ee, self.span))
+    }
+}
+
+impl WithUnit {
+    fn eval_in<N: Numeric>(&self, scope: &Scope<N>) -> Result<N, CalError> {
+        let mode = scope.env().mode();
+        let value = self.expr.eval_in(scope)?;
+        // `2x` multiplies by `x`, the unit `x` only counts when there is no such variable
+        let var = match &self.unit.factors[..] {
+            [(name, exp)] if self.scales => scope.get(name).map(|var| (var, *exp)),
+            _ => None,
+        };
+        let value = match var {
+            Some((var, exp)) => {
+                let exp = N::from_literal(&BigDecimal::from(exp), mode);
+                var.and_then(|var| var.pow(&exp?)).and_then(|scale| value.mul(&scale))
+            }
+            None => {
+                let unit = self.unit.resolve(|name| scope.env().unit(name))?;
+                measures::<N>().and_then(|measures| (measures.with_unit)(&value, &unit))
+            }
+        };
+        value.or_else(|ee| CalError::eval(ee, self.span))
+    }
+}
+
+impl Call {
+    fn eval_in<N: Numeric>(&self, scope: &Scope<N>) -> Result<N, CalError> {
+        let arity_mismatch = |expected| {
+            CalError::eval(
+                EvalError::ArityMismatch {
+                    name: self.name.clone(),
+                    expected,
+                    found: self.args.len(),
+                },
+                self.span,
+            )
+        };
+        let args = || {
+            self.args
+                .iter()
+                .map(|arg| arg.eval_in(scope))
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        if let Some(function) = scope.env().function(&self.name) {
+            if function.arity() != Arity::exact(self.args.len()) {
+                return arity_mismatch(function.arity());
             }
+            let limit = scope.env().max_depth();
+            if scope.depth() >= limit {
+                return CalError::eval(EvalError::RecursionLimit(limit), self.span);
+            }
+            // the body only sees its own parameters and the globals
+            let local = scope.enter(function.params(), args()?);
+            return function.body().eval_in(&local);
+        }
+
+        let Some(callable) = scope.env().registry().get(&self.name) else {
+            return CalError::eval(EvalError::UnknownFunction(self.name.clone()), self.span);
+        };
+        if !callable.arity().accepts(self.args.len()) {
+            return arity_mismatch(callable.arity());
         }
+        callable
+            .call(&args()?)
+            .and_then(|value| value.to_mode(scope.env().mode()))
+            .or_else(|ee| CalError::eval(ee, self.span))
+    }
+}
+
+impl Stmt {
+    pub fn span(&self) -> Span {
+        match self {
+            Stmt::Let(l) => l.span,
+            Stmt::Def(def) => def.span,
+            Stmt::Expr(expr) => expr.span(),
+        }
+    }
+
+    /// Execute the statement, returning the value it evaluates to or assigns.
+    /// Function definitions have no value.
+    pub fn exec<N: Numeric>(&self, env: &mut Environment<N>) -> Result<Option<N>, CalError> {
+        match self {
+            Stmt::Let(l) => {
+                let value = l.expr.eval_with(env)?;
+                env.set(&l.name, value.clone());
+                Ok(Some(value))
+            }
+            Stmt::Def(def) => {
+                let function = Function::new(def.params.clone(), def.body.clone());
+                if let Err(ee) = env.define(&def.name, function) {
+                    return CalError::eval(ee, def.span);
+                }
+                Ok(None)
+            }
+            Stmt::Expr(expr) => expr.eval_with(env).map(Some),
+        }
+    }
+}
+
+impl Program {
+    pub fn stmts(&self) -> &[Stmt] {
+        &self.stmts
+    }
+
+    /// Run every statement in order, returning the value of the last one,
+    /// or `None` for an empty program or one ending in a definition.
+    pub fn eval_with<N: Numeric>(&self, env: &mut Environment<N>) -> Result<Option<N>, CalError> {
+        let mut last = None;
+        for stmt in &self.stmts {
+            last = stmt.exec(env)?;
+        }
+        Ok(last)
     }
 }
 
@@ -75,20 +433,255 @@ impl<'a> TryFrom<&'a str> for LookAhead<Chars<'a>> {
 }
 
 impl<I: Iterator<Item = char>> LookAhead<I> {
+    /// A parser knowing the built-in operators.
     pub fn new(chars: I) -> Result<Self, CalError> {
-        let mut lexer = Lexer::new(chars);
+        Self::with_grammar(chars, Grammar::builtin())
+    }
+
+    /// A parser knowing the operators of `grammar`, usually [`OperatorTable::grammar`] of the
+    /// environment the expression is evaluated in.
+    ///
+    /// [`OperatorTable::grammar`]: crate::express::operator::OperatorTable::grammar
+    pub fn with_grammar(chars: I, grammar: Rc<Grammar>) -> Result<Self, CalError> {
+        let lexer = Lexer::new(chars).with_symbols(grammar.symbols());
+        Self::from_lexer(lexer, grammar)
+    }
+
+    /// A parser reading the tokens of `lexer`, which should know the symbols of `grammar`.
+    pub fn from_lexer(mut lexer: Lexer<I>, grammar: Rc<Grammar>) -> Result<Self, CalError> {
         Ok(Self {
             peek: lexer.read()?,
+            last: Span::default(),
             lexer,
+            grammar,
+            implicit: Implicit::default(),
+            percent: Percent::default(),
+            errors: None,
         })
     }
 
+    /// A parser that goes on after a syntax error rather than failing, to report every error of a
+    /// program at once.
+    ///
+    /// It drops chars the lexer cannot read, puts an [`Expr::Error`] in place of a missing operand,
+    /// takes a missing `)` as read and skips the rest of a statement it cannot make sense of. A
+    /// statement with any error in it becomes an [`Expr::Error`] as a whole, so it never runs.
+    /// [`LookAhead::take_errors`] hands out what it found.
+    pub fn recovering(chars: I, grammar: Rc<Grammar>) -> Self {
+        let lexer = Lexer::new(chars).with_symbols(grammar.symbols());
+        let mut parser = Self {
+            peek: Spanned::new(Token::EOF, Span::new(0, 0)),
+            last: Span::default(),
+            lexer,
+            grammar,
+            implicit: Implicit::default(),
+            percent: Percent::default(),
+            errors: Some(vec![]),
+        };
+        // lex errors are noted, so there always is a first token
+        if let Ok(token) = parser.read() {
+            parser.peek = token;
+        }
+        parser
+    }
+
+    /// The errors a recovering parser noted so far, in the order it found them.
+    pub fn take_errors(&mut self) -> Vec<CalError> {
+        self.errors.as_mut().map(mem::take).unwrap_or_default()
+    }
+
+    /// Note `err` and go on with `fallback` when recovering, otherwise fail with `err`.
+    fn recover<T>(&mut self, err: CalError, fallback: T) -> Result<T, CalError> {
+        match &mut self.errors {
+            Some(errors) => {
+                errors.push(err);
+                Ok(fallback)
+            }
+            None => Err(err),
+        }
+    }
+
+    /// How many errors were noted so far, always 0 when not recovering.
+    fn noted(&self) -> usize {
+        self.errors.as_ref().map_or(0, Vec::len)
+    }
+
+    fn read(&mut self) -> Result<Spanned<Token>, CalError> {
+        loop {
+            match self.lexer.read() {
+                // the lexer is past the chars it could not read
+                Err(err @ CalError::LexError(..)) => self.recover(err, ())?,
+                token => return token,
+            }
+        }
+    }
+
+    /// Skip to the end of the statement the tokens of `span` belong to, returning the span of all.
+    fn skip_stmt(&mut self, mut span: Span) -> Result<Span, CalError> {
+        while !matches!(self.peek(), Token::Semicolon | Token::Newline | Token::EOF) {
+            span = span.to(self.pop()?.span);
+        }
+        Ok(span)
+    }
+
+    /// Parse adjacent operands as described on [`Implicit`].
+    pub fn with_implicit(mut self, implicit: Implicit) -> Self {
+        self.implicit = implicit;
+        self
+    }
+
+    /// Read `%` as described on [`Percent`].
+    pub fn with_percent(mut self, percent: Percent) -> Self {
+        self.percent = percent;
+        self
+    }
+
+    /// How `token` is parsed as an operator of `fixity`, if it is one.
+    fn syntax(&self, token: &Token, fixity: Fixity) -> Option<Syntax> {
+        token.symbol().and_then(|symbol| self.grammar.get(symbol, fixity))
+    }
+
+    /// Whether the next token starts an operand adjacent to the one before it.
+    ///
+    /// Only a name, `(` or a prefix operator like `√` does, so `2 3` stays an error rather than `6`,
+    /// but none that is also an infix operator, so `2 -3` is a subtraction.
+    fn peek_adjacent(&self) -> bool {
+        let token = self.peek();
+        if self.syntax(token, Fixity::Infix).is_some() {
+            return false;
+        }
+        match token {
+            Token::Ident(name) => name != "in",
+            Token::OpenPh => true,
+            token => self.syntax(token, Fixity::Prefix).is_some(),
+        }
+    }
+
+    /// The precedence of the next token as an infix operator, counting an adjacent operand as `*`.
+    fn peek_prec(&self) -> i8 {
+        let token = self.peek();
+        match token {
+            Token::To => CONVERT_PREC,
+            Token::Ident(name) if name == "in" => CONVERT_PREC,
+            _ if self.peek_adjacent() => match self.implicit {
+                Implicit::Tight => TIGHT_PREC,
+                Implicit::Loose => self.grammar.get("*", Fixity::Infix).map_or(TIGHT_PREC, |syntax| syntax.prec),
+                Implicit::Strict => -1,
+            },
+            _ => self.syntax(token, Fixity::Infix).map_or(-1, |syntax| syntax.prec),
+        }
+    }
+
     pub fn peek(&self) -> &Token {
-        &self.peek
+        &self.peek.node
+    }
+
+    pub fn peek_span(&self) -> Span {
+        self.peek.span
+    }
+
+    pub fn pop(&mut self) -> Result<Spanned<Token>, CalError> {
+        let next = self.read()?;
+        self.last = self.peek.span;
+        Ok(mem::replace(&mut self.peek, next))
+    }
+
+    /// Take back `token`, popped last while `last` was the span of the token before it.
+    fn unpop(&mut self, token: Spanned<Token>, last: Span) {
+        let next = mem::replace(&mut self.peek, token);
+        self.lexer.unread(next);
+        self.last = last;
+    }
+
+    /// Program ::= [Stmt] {(";" | NEWLINE) [Stmt]} EOF;
+    ///
+    /// Newlines separate statements here, while within a single expression they are whitespace.
+    pub fn parse_program(&mut self) -> Result<Program, CalError> {
+        self.lexer.set_statements(true);
+        let mut stmts = vec![];
+        loop {
+            // what follows an error is often an error only because of it, as the `2` of `1 @ 2`
+            let noted = self.noted();
+            match self.peek() {
+                Token::EOF => break,
+                Token::Semicolon | Token::Newline => {
+                    self.pop()?;
+                }
+                _ => {
+                    let start = self.peek_span();
+                    let mut stmt = match self.parse_stmt() {
+                        Ok(stmt) => stmt,
+                        Err(err) => {
+                            let mut span = start.to(err.span());
+                            // the tokens of this statement read before the error
+                            if self.last.start >= start.start {
+                                span = span.to(self.last);
+                            }
+                            if self.noted() == noted {
+                                self.recover(err, ())?;
+                            }
+                            Stmt::Expr(Expr::Error(self.skip_stmt(span)?))
+                        }
+                    };
+                    if !matches!(self.peek(), Token::Semicolon | Token::Newline | Token::EOF) {
+                        if self.noted() == noted {
+                            let message = format!("Expect ';' or newline, get '{:?}'", self.peek());
+                            self.recover(CalError::SyntaxError(message, self.peek_span()), ())?;
+                        }
+                        self.skip_stmt(start)?;
+                    }
+                    // recovered from, as the missing `)` of `(1 + 2`, but still not what was meant
+                    if self.noted() != noted && !matches!(stmt, Stmt::Expr(Expr::Error(_))) {
+                        stmt = Stmt::Expr(Expr::Error(start.to(self.last)));
+                    }
+                    stmts.push(stmt);
+                }
+            }
+        }
+        Ok(Program { stmts })
     }
 
-    pub fn pop(&mut self) -> Result<Token, CalError> {
-        Ok(mem::replace(&mut self.peek, self.lexer.read()?))
+    /// Stmt ::= ["let"] IDENT "=" Expr | ["let"] IDENT "(" [IDENT {"," IDENT}] ")" "=" Expr | Expr;
+    pub fn parse_stmt(&mut self) -> Result<Stmt, CalError> {
+        let let_span = match self.peek() {
+            Token::Let => Some(self.pop()?.span),
+            _ => None,
+        };
+
+        let lhs = self.parse_expr()?;
+        if !matches!(self.peek(), Token::Assign) {
+            return match let_span {
+                Some(_) => CalError::syn(format!("Expect '=', get '{:?}'", self.peek()).as_str(), self.peek_span()),
+                None => Ok(Stmt::Expr(lhs)),
+            };
+        }
+        // pop '='
+        self.pop()?;
+        let rhs = self.parse_expr()?;
+        let span = let_span.unwrap_or(lhs.span()).to(rhs.span());
+
+        match lhs {
+            Expr::Var(name, _) => Ok(Stmt::Let(Box::new(Let { name, expr: rhs, span }))),
+            Expr::Call(call) => {
+                let mut params: Vec<String> = vec![];
+                for arg in call.args {
+                    match arg {
+                        Expr::Var(param, _) if !params.contains(&param) => params.push(param),
+                        Expr::Var(param, span) => {
+                            return CalError::syn(format!("Duplicate parameter '{param}'").as_str(), span)
+                        }
+                        _ => return CalError::syn("Expect {identifier} as parameter", arg.span()),
+                    }
+                }
+                Ok(Stmt::Def(Box::new(Def {
+                    name: call.name,
+                    params,
+                    body: rhs,
+                    span,
+                })))
+            }
+            _ => CalError::syn("Only a variable or a function can be assigned to", lhs.span()),
+        }
     }
 
     pub fn parse_expr(&mut self) -> Result<Expr, CalError> {
@@ -100,64 +693,267 @@ impl<I: Iterator<Item = char>> LookAhead<I> {
     fn parse_binop(&mut self, expr_prec: i8, mut lhs: Expr) -> Result<Expr, CalError> {
         // The loop continues when the current operator's priority is same as the next operator's
         loop {
-            let tok_prec = get_tok_prec(self.peek());
+            let adjacent = self.peek_adjacent();
+            if adjacent && self.implicit == Implicit::Strict {
+                let token = self.peek();
+                return CalError::syn(format!("Expect an operator before '{token:?}'").as_str(), self.peek_span());
+            }
+            let tok_prec = self.peek_prec();
             if tok_prec < expr_prec {
                 return Ok(lhs);
             }
 
-            let op: OpSymbol = self.pop()?.into();
+            if matches!(self.peek(), Token::To) || matches!(self.peek(), Token::Ident(name) if name == "in") {
+                // pop 'to' or 'in'
+                self.pop()?;
+                let unit = self.parse_unit_expr()?;
+                let span = lhs.span().to(unit.span);
+                lhs = Expr::Convert(Box::new(Convert { expr: lhs, unit, span }));
+                continue;
+            }
+
+            // an adjacent operand is left for `parse_unit`
+            let (op, assoc) = match adjacent {
+                true => (String::from("*"), Assoc::Left),
+                false => {
+                    let Spanned { node: token, span } = self.pop()?;
+                    match (self.syntax(&token, Fixity::Infix), token.symbol()) {
+                        (Some(syntax), Some(symbol)) => (String::from(symbol), syntax.assoc),
+                        _ => return CalError::syn(format!("Expect an operator, get '{token:?}'").as_str(), span),
+                    }
+                }
+            };
+            let literal = matches!(self.peek(), Token::Number(_) | Token::Imaginary(_));
             let mut rhs = self.parse_unit()?;
 
-            let next_prec = get_tok_prec(self.peek());
-            if tok_prec < next_prec {
-                // The higher the op-priority the deeper this method recursive calls
-                rhs = self.parse_binop(tok_prec + 1, rhs)?;
+            let next_prec = self.peek_prec();
+            if tok_prec < next_prec || (tok_prec == next_prec && assoc == Assoc::Right) {
+                // The higher the op-priority the deeper this method recursive calls,
+                // and a right-associative operator also takes the next one of its own priority
+                let min_prec = match assoc {
+                    Assoc::Left => tok_prec.saturating_add(1),
+                    Assoc::Right => tok_prec,
+                };
+                rhs = self.parse_binop(min_prec, rhs)?;
             }
 
-            lhs = Expr::BinOp(Box::new(BinOp { op, lhs, rhs }))
+            let span = lhs.span().to(rhs.span());
+            // operators as tight as `*`, like `/`, take the number of `2x` alone in loose mode
+            let loose = self.implicit == Implicit::Loose
+                && self.grammar.get("*", Fixity::Infix).is_some_and(|syntax| syntax.prec == tok_prec);
+            lhs = match rhs {
+                // `1/2x` is read as `(1/2)x`
+                Expr::WithUnit(wu) if loose && literal => {
+                    let WithUnit { expr, unit, scales, .. } = *wu;
+                    let inner = lhs.span().to(expr.span());
+                    let expr = Expr::BinOp(Box::new(BinOp { op, lhs, rhs: expr, span: inner }));
+                    Expr::WithUnit(Box::new(WithUnit { expr, unit, scales, span }))
+                }
+                rhs => Expr::BinOp(Box::new(BinOp { op, lhs, rhs, span })),
+            }
         }
     }
 
+    /// Unit ::= (Num | Var | Unary | Ph) {SUPERSCRIPT | POSTFIX};
+    ///
+    /// The postfix `%` is only read in [`Percent::Calculator`] mode.
     pub fn parse_unit(&mut self) -> Result<Expr, CalError> {
         let token = self.peek();
-        match token {
-            Token::Number(_) => self.parse_num(),
-            Token::Plus | Token::Minus => self.parse_unary(),
-            Token::OpenPh => self.parse_ph(),
-            _ => CalError::syn(format!("Unrecognized token '{token:?}'").as_str()),
+        let mut unit = match token {
+            Token::Number(_) | Token::Imaginary(_) | Token::Date(_) | Token::Time(_) => self.parse_num()?,
+            Token::OpenPh => self.parse_ph()?,
+            _ if self.syntax(token, Fixity::Prefix).is_some() => self.parse_unary()?,
+            Token::Ident(_) => self.parse_var()?,
+            _ => {
+                // left for the caller, which may go on at an operator, `)` or the end of the statement
+                let span = self.peek_span();
+                let err = CalError::SyntaxError(format!("Unrecognized token '{token:?}'"), span);
+                return self.recover(err, Expr::Error(span));
+            }
+        };
+        loop {
+            match self.peek() {
+                // `x²` is `x^2`
+                Token::Superscript(exp) => {
+                    let exp = *exp;
+                    let exp_span = self.pop()?.span;
+                    let span = unit.span().to(exp_span);
+                    let rhs = Expr::Num(BigDecimal::from(exp), exp_span);
+                    unit = Expr::BinOp(Box::new(BinOp { op: String::from("^"), lhs: unit, rhs, span }));
+                }
+                Token::Percent if self.percent == Percent::Modulo => return Ok(unit),
+                token if self.syntax(token, Fixity::Postfix).is_some() => {
+                    let Spanned { node: token, span: op_span } = self.pop()?;
+                    let op = String::from(token.symbol().unwrap_or_default());
+                    let span = unit.span().to(op_span);
+                    unit = Expr::UnaryOp(Box::new(UnaryOp { op, fixity: Fixity::Postfix, num: unit, span }));
+                }
+                _ => return Ok(unit),
+            }
         }
     }
 
+    /// Num ::= (NUMBER | IMAGINARY) [UnitFactor] | DATE [TIME] | TIME;
+    ///
+    /// An identifier right after a number is its unit, so `5 in` is five inches, or the variable
+    /// it is multiplied by as in `2x`, unless that identifier is an infix operator as in `7 mod 2`
+    /// or is called as in `2 sin(x)`, which is left to `parse_binop` as any other adjacent operand.
     pub fn parse_num(&mut self) -> Result<Expr, CalError> {
-        let token = self.pop()?;
-        if let Token::Number(n) = token {
-            Ok(Expr::Num(n))
-        } else {
-            CalError::syn(format!("Expect {{number}}, get '{token:?}'").as_str())
+        let Spanned { node: token, span } = self.pop()?;
+        let num = match token {
+            Token::Number(n) => Expr::Num(n, span),
+            Token::Imaginary(n) => Expr::Imag(n, span),
+            Token::Date(date) => {
+                let Token::Time(time) = *self.peek() else {
+                    return Ok(Expr::Date(date, span));
+                };
+                let time_span = self.pop()?.span;
+                return Ok(Expr::Date(date + time, span.to(time_span)));
+            }
+            Token::Time(time) => return Ok(Expr::Clock(time, span)),
+            _ => return CalError::syn(format!("Expect {{number}}, get '{token:?}'").as_str(), span),
+        };
+        // a word operator like `mod` is not a unit
+        if !matches!(self.peek(), Token::Ident(_)) || self.syntax(self.peek(), Fixity::Infix).is_some() {
+            return Ok(num);
+        }
+        let last = self.last;
+        let ident = self.pop()?;
+        if matches!(self.peek(), Token::OpenPh) && self.implicit != Implicit::Strict {
+            self.unpop(ident, last);
+            return Ok(num);
+        }
+        let Spanned { node: Token::Ident(name), span: name_span } = ident else {
+            unreachable!("peeked an identifier");
+        };
+        let (exp, unit_span) = self.parse_unit_exponent(name_span)?;
+        let unit = UnitExpr {
+            factors: vec![(name, exp)],
+            span: unit_span,
+        };
+        let span = span.to(unit_span);
+        let scales = self.implicit != Implicit::Strict;
+        Ok(Expr::WithUnit(Box::new(WithUnit { expr: num, unit, scales, span })))
+    }
+
+    /// UnitExpr ::= UnitFactor {("*" | "/") UnitFactor};
+    pub fn parse_unit_expr(&mut self) -> Result<UnitExpr, CalError> {
+        let (name, exp, mut span) = self.parse_unit_factor()?;
+        let mut factors = vec![(name, exp)];
+        while matches!(self.peek(), Token::Multiply | Token::Division) {
+            let sign = match self.pop()?.node {
+                Token::Division => -1,
+                _ => 1,
+            };
+            let (name, exp, factor_span) = self.parse_unit_factor()?;
+            factors.push((name, sign * exp));
+            span = span.to(factor_span);
+        }
+        Ok(UnitExpr { factors, span })
+    }
+
+    /// UnitFactor ::= IDENT ["^" ["-"] NUMBER | SUPERSCRIPT];
+    fn parse_unit_factor(&mut self) -> Result<(String, i32, Span), CalError> {
+        let Spanned { node: token, span } = self.pop()?;
+        let Token::Ident(name) = token else {
+            return CalError::syn(format!("Expect {{unit}}, get '{token:?}'").as_str(), span);
+        };
+        let (exp, span) = self.parse_unit_exponent(span)?;
+        Ok((name, exp, span))
+    }
+
+    /// The optional exponent of a unit named at `span`, together with the span of both.
+    fn parse_unit_exponent(&mut self, span: Span) -> Result<(i32, Span), CalError> {
+        if let Token::Superscript(exp) = *self.peek() {
+            let exp_span = self.pop()?.span;
+            return Ok((exp, span.to(exp_span)));
+        }
+        if !matches!(self.peek(), Token::Exponential) {
+            return Ok((1, span));
+        }
+        // pop '^'
+        self.pop()?;
+        let sign = match self.peek() {
+            Token::Minus => {
+                self.pop()?;
+                -1
+            }
+            _ => 1,
+        };
+        let Spanned { node: token, span: exp_span } = self.pop()?;
+        let exp = match &token {
+            Token::Number(n) if n.is_integer() => n.to_i32(),
+            _ => None,
+        };
+        match exp {
+            Some(exp) => Ok((sign * exp, span.to(exp_span))),
+            None => CalError::syn(format!("Expect {{integer}} exponent, get '{token:?}'").as_str(), exp_span),
         }
     }
 
+    /// Var ::= IDENT | IDENT "(" [Expr {"," Expr}] ")";
+    pub fn parse_var(&mut self) -> Result<Expr, CalError> {
+        let Spanned { node: token, span } = self.pop()?;
+        let Token::Ident(name) = token else {
+            return CalError::syn(format!("Expect {{identifier}}, get '{token:?}'").as_str(), span);
+        };
+        if !matches!(self.peek(), Token::OpenPh) {
+            return Ok(Expr::Var(name, span));
+        }
+        self.parse_call(name, span)
+    }
+
+    /// The arguments of a call to `name`, which was read at `span`.
+    fn parse_call(&mut self, name: String, span: Span) -> Result<Expr, CalError> {
+        // pop '('
+        self.pop()?;
+        let mut args = vec![];
+        if !matches!(self.peek(), Token::ClosePh) {
+            loop {
+                args.push(self.parse_expr()?);
+                if !matches!(self.peek(), Token::Comma) {
+                    break;
+                }
+                self.pop()?;
+            }
+        }
+        if !matches!(self.peek(), Token::ClosePh) {
+            let err = CalError::SyntaxError(format!("Expect ',' or ')', get '{:?}'", self.peek()), self.peek_span());
+            let span = args.last().map_or(span, |arg| span.to(arg.span()));
+            return self.recover(err, Expr::Call(Box::new(Call { name, args, span })));
+        }
+        // pop ')'
+        let span = span.to(self.pop()?.span);
+        Ok(Expr::Call(Box::new(Call { name, args, span })))
+    }
+
+    /// Unary ::= PREFIX Unit {INFIX Unit};
+    ///
+    /// The operand extends over the infix operators of at least the precedence of the prefix operator,
+    /// so a sign binds below `^` as in `-2^2`, which is `-(2^2)`, but `-2*3` is `(-2)*3`.
     pub fn parse_unary(&mut self) -> Result<Expr, CalError> {
-        let op_tok = self.pop()?;
-        let op = match op_tok {
-            Token::Plus => OpSymbol::Add,
-            Token::Minus => OpSymbol::Subtract,
-            _ => CalError::syn(format!("Expect '+' or '-', get '{op_tok:?}'").as_str())?,
+        let Spanned { node: op_tok, span: op_span } = self.pop()?;
+        let (Some(syntax), Some(symbol)) = (self.syntax(&op_tok, Fixity::Prefix), op_tok.symbol()) else {
+            return CalError::syn(format!("Expect a prefix operator, get '{op_tok:?}'").as_str(), op_span);
         };
-        let num = self.parse_num()?;
+        let op = String::from(symbol);
+        let operand = self.parse_unit()?;
+        let num = self.parse_binop(syntax.prec, operand)?;
+        let span = op_span.to(num.span());
 
-        Ok(Expr::UnaryOp(Box::new(UnaryOp { op, num })))
+        Ok(Expr::UnaryOp(Box::new(UnaryOp { op, fixity: Fixity::Prefix, num, span })))
     }
 
     pub fn parse_ph(&mut self) -> Result<Expr, CalError> {
         // pop '('
         let _ = self.pop();
         let expr = self.parse_expr()?;
-        // pop ')'
-        let close_tok = self.pop()?;
-        if !matches!(close_tok, Token::ClosePh) {
-            return CalError::syn(format!("Expect ')', get '{close_tok:?}'").as_str());
+        if !matches!(self.peek(), Token::ClosePh) {
+            let err = CalError::SyntaxError(format!("Expect ')', get '{:?}'", self.peek()), self.peek_span());
+            return self.recover(err, expr);
         }
+        // pop ')'
+        self.pop()?;
         Ok(expr)
     }
 }
@@ -0,0 +1,64 @@
+/// A half-open range `[start, end)` of byte offsets into the source string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// The smallest span covering both `self` and `other`.
+    pub fn to(self, other: Span) -> Self {
+        Self {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    /// Render `src` with a caret underline below the region this span covers, e.g.
+    /// ```text
+    /// 1+2.2.2
+    ///   ^^^^^
+    /// ```
+    pub fn underline(&self, src: &str) -> String {
+        let start = self.start.min(src.len());
+        let end = self.end.clamp(start, src.len());
+
+        let line_start = src[..start].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = src[start..].find('\n').map_or(src.len(), |i| start + i);
+        let line = &src[line_start..line_end];
+
+        // keep tabs so the caret lines up with the source line
+        let pad: String = src[line_start..start]
+            .chars()
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+        let width = src[start..end.min(line_end)].chars().count().max(1);
+
+        format!("{line}\n{pad}{}", "^".repeat(width))
+    }
+}
+
+/// A value together with the span of source it was read from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<T> {
+    pub node: T,
+    pub span: Span,
+}
+
+impl<T> Spanned<T> {
+    pub fn new(node: T, span: Span) -> Self {
+        Self { node, span }
+    }
+}
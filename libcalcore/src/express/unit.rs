@@ -0,0 +1,369 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use bigdecimal::BigDecimal;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::{One, Pow};
+
+use super::error::EvalError;
+
+/// The base quantities every unit is measured in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base {
+    Length,
+    Mass,
+    Time,
+    Current,
+    Temperature,
+    Amount,
+    Luminosity,
+    /// Money, measured in the base currency of the [`RateTable`](super::currency::RateTable)
+    Currency,
+}
+
+impl Base {
+    pub const ALL: [Base; 8] = [
+        Base::Length,
+        Base::Mass,
+        Base::Time,
+        Base::Current,
+        Base::Temperature,
+        Base::Amount,
+        Base::Luminosity,
+        Base::Currency,
+    ];
+
+    /// The symbol of the SI unit, e.g. `kg` for [`Base::Mass`].
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Base::Length => "m",
+            Base::Mass => "kg",
+            Base::Time => "s",
+            Base::Current => "A",
+            Base::Temperature => "K",
+            Base::Amount => "mol",
+            Base::Luminosity => "cd",
+            Base::Currency => "¤",
+        }
+    }
+}
+
+/// The exponent of each [`Base`] in a quantity, e.g. `m/s` is length¹ time⁻¹.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Dimension([i8; Base::ALL.len()]);
+
+impl Dimension {
+    /// The dimension of plain numbers.
+    pub const NONE: Self = Self([0; Base::ALL.len()]);
+
+    pub fn base(base: Base) -> Self {
+        let mut dim = Self::NONE;
+        dim.0[base as usize] = 1;
+        dim
+    }
+
+    pub fn exponent(&self, base: Base) -> i8 {
+        self.0[base as usize]
+    }
+
+    pub fn is_none(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    pub fn mul(&self, rhs: &Self) -> Result<Self, EvalError> {
+        self.zip(rhs, i8::checked_add)
+    }
+
+    pub fn div(&self, rhs: &Self) -> Result<Self, EvalError> {
+        self.zip(rhs, i8::checked_sub)
+    }
+
+    pub fn powi(&self, exp: i32) -> Result<Self, EvalError> {
+        let exp = i8::try_from(exp).map_err(|_| EvalError::TooLarge)?;
+        let mut dim = Self::NONE;
+        for (out, e) in dim.0.iter_mut().zip(self.0) {
+            *out = e.checked_mul(exp).ok_or(EvalError::TooLarge)?;
+        }
+        Ok(dim)
+    }
+
+    /// The `n`th root, if every exponent is divisible by `n`.
+    pub fn root(&self, n: i8) -> Option<Self> {
+        let mut dim = Self::NONE;
+        for (out, e) in dim.0.iter_mut().zip(self.0) {
+            if e % n != 0 {
+                return None;
+            }
+            *out = e / n;
+        }
+        Some(dim)
+    }
+
+    fn zip(&self, rhs: &Self, op: fn(i8, i8) -> Option<i8>) -> Result<Self, EvalError> {
+        let mut dim = Self::NONE;
+        for ((out, l), r) in dim.0.iter_mut().zip(self.0).zip(rhs.0) {
+            *out = op(l, r).ok_or(EvalError::TooLarge)?;
+        }
+        Ok(dim)
+    }
+}
+
+/// SI units, e.g. `m^2*kg/s^2`, or `1` for a plain number.
+impl Display for Dimension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let part = |base: Base, exp: i8| match exp {
+            1 => String::from(base.symbol()),
+            _ => format!("{}^{exp}", base.symbol()),
+        };
+        let numer: Vec<_> = Base::ALL
+            .iter()
+            .filter(|b| self.exponent(**b) > 0)
+            .map(|b| part(*b, self.exponent(*b)))
+            .collect();
+        let denom: Vec<_> = Base::ALL
+            .iter()
+            .filter(|b| self.exponent(**b) < 0)
+            .map(|b| part(*b, -self.exponent(*b)))
+            .collect();
+        match (numer.is_empty(), denom.is_empty()) {
+            (true, true) => write!(f, "1"),
+            (false, true) => write!(f, "{}", numer.join("*")),
+            (true, false) => write!(f, "1/{}", denom.join("/")),
+            (false, false) => write!(f, "{}/{}", numer.join("*"), denom.join("/")),
+        }
+    }
+}
+
+/// A unit of measurement: `factor` SI units of `dim`, e.g. `ft` is 0.3048 `m`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unit {
+    label: String,
+    factor: BigRational,
+    dim: Dimension,
+    /// Amounts in this unit are shown with exactly this many decimals, like cents for `USD`
+    decimals: Option<u32>,
+}
+
+impl Unit {
+    pub fn new(label: &str, factor: BigRational, dim: Dimension) -> Self {
+        Self {
+            label: String::from(label),
+            factor,
+            dim,
+            decimals: None,
+        }
+    }
+
+    /// A currency worth `rate` of the base currency, rounded to `decimals` places when shown.
+    pub fn currency(code: &str, rate: BigRational, decimals: u32) -> Self {
+        Self {
+            decimals: Some(decimals),
+            ..Self::new(code, rate, Dimension::base(Base::Currency))
+        }
+    }
+
+    /// The unit of plain numbers.
+    pub fn one() -> Self {
+        Self::new("1", BigRational::one(), Dimension::NONE)
+    }
+
+    pub fn label(&self) -> &str {
+        &self.label
+    }
+
+    pub fn factor(&self) -> &BigRational {
+        &self.factor
+    }
+
+    pub fn dim(&self) -> Dimension {
+        self.dim
+    }
+
+    pub fn decimals(&self) -> Option<u32> {
+        self.decimals
+    }
+
+    pub fn with_label(self, label: &str) -> Self {
+        Self {
+            label: String::from(label),
+            ..self
+        }
+    }
+
+    pub fn mul(&self, rhs: &Self) -> Result<Self, EvalError> {
+        let label = format!("{}*{}", self.label, rhs.label);
+        Ok(Self::new(&label, &self.factor * &rhs.factor, self.dim.mul(&rhs.dim)?))
+    }
+
+    pub fn powi(&self, exp: i32) -> Result<Self, EvalError> {
+        let label = format!("{}^{exp}", self.label);
+        Ok(Self::new(&label, Pow::pow(&self.factor, exp), self.dim.powi(exp)?))
+    }
+}
+
+const PREFIXES: [(&str, i32); 20] = [
+    ("Q", 30),
+    ("R", 27),
+    ("Y", 24),
+    ("Z", 21),
+    ("E", 18),
+    ("P", 15),
+    ("T", 12),
+    ("G", 9),
+    ("M", 6),
+    ("k", 3),
+    ("h", 2),
+    ("da", 1),
+    ("d", -1),
+    ("c", -2),
+    ("m", -3),
+    ("µ", -6),
+    ("u", -6),
+    ("n", -9),
+    ("p", -12),
+    ("f", -15),
+];
+
+#[derive(Debug, Clone)]
+struct Entry {
+    unit: Unit,
+    prefixable: bool,
+}
+
+/// The units known to an [`Environment`](super::env::Environment), looked up by name.
+///
+/// Units marked as prefixable also accept SI prefixes, so defining `m` gives `km` and `mm`.
+#[derive(Debug, Clone)]
+pub struct UnitTable {
+    units: HashMap<String, Entry>,
+}
+
+impl Default for UnitTable {
+    fn default() -> Self {
+        Self::si()
+    }
+}
+
+impl UnitTable {
+    /// A table without any units.
+    pub fn new() -> Self {
+        Self { units: HashMap::new() }
+    }
+
+    /// The SI units with their common multiples, and the imperial units of length and mass.
+    pub fn si() -> Self {
+        use Base::*;
+        let mut table = Self::new();
+        let dim = |exps: &[(Base, i32)]| {
+            exps.iter()
+                .try_fold(Dimension::NONE, |dim, (base, exp)| dim.mul(&Dimension::base(*base).powi(*exp)?))
+                .expect("dimension in range")
+        };
+        let length = dim(&[(Length, 1)]);
+        let mass = dim(&[(Mass, 1)]);
+        let time = dim(&[(Time, 1)]);
+        let energy = dim(&[(Mass, 1), (Length, 2), (Time, -2)]);
+        let pressure = dim(&[(Mass, 1), (Length, -1), (Time, -2)]);
+        let definitions: &[(&str, &str, Dimension, bool)] = &[
+            ("m", "1", length, true),
+            ("in", "0.0254", length, false),
+            ("ft", "0.3048", length, false),
+            ("yd", "0.9144", length, false),
+            ("mi", "1609.344", length, false),
+            ("g", "0.001", mass, true),
+            ("kg", "1", mass, false),
+            ("t", "1000", mass, false),
+            ("lb", "0.45359237", mass, false),
+            ("oz", "0.028349523125", mass, false),
+            ("s", "1", time, true),
+            ("min", "60", time, false),
+            ("h", "3600", time, false),
+            ("day", "86400", time, false),
+            ("week", "604800", time, false),
+            ("yr", "31557600", time, false),
+            ("seconds", "1", time, false),
+            ("minutes", "60", time, false),
+            ("hours", "3600", time, false),
+            ("days", "86400", time, false),
+            ("weeks", "604800", time, false),
+            ("A", "1", dim(&[(Current, 1)]), true),
+            ("K", "1", dim(&[(Temperature, 1)]), true),
+            ("mol", "1", dim(&[(Amount, 1)]), true),
+            ("cd", "1", dim(&[(Luminosity, 1)]), true),
+            ("Hz", "1", dim(&[(Time, -1)]), true),
+            ("N", "1", dim(&[(Mass, 1), (Length, 1), (Time, -2)]), true),
+            ("J", "1", energy, true),
+            ("Wh", "3600", energy, true),
+            ("cal", "4.184", energy, true),
+            ("W", "1", dim(&[(Mass, 1), (Length, 2), (Time, -3)]), true),
+            ("Pa", "1", pressure, true),
+            ("bar", "100000", pressure, true),
+            ("atm", "101325", pressure, false),
+            ("C", "1", dim(&[(Current, 1), (Time, 1)]), true),
+            ("V", "1", dim(&[(Mass, 1), (Length, 2), (Time, -3), (Current, -1)]), true),
+            ("L", "0.001", dim(&[(Length, 3)]), true),
+            ("ha", "10000", dim(&[(Length, 2)]), false),
+            ("mph", "0.44704", dim(&[(Length, 1), (Time, -1)]), false),
+            ("rad", "1", Dimension::NONE, true),
+            (
+                "deg",
+                "0.01745329251994329576923690768488612713442871888541725456097191440",
+                Dimension::NONE,
+                false,
+            ),
+        ];
+        for (name, factor, dim, prefixable) in definitions {
+            let factor = BigDecimal::from_str(factor).expect("valid unit factor");
+            let unit = Unit::new(name, decimal_to_rational(&factor), *dim);
+            if *prefixable {
+                table.define_prefixable(unit);
+            } else {
+                table.define(unit);
+            }
+        }
+        table.define(table.get("deg").expect("deg is defined").with_label("°"));
+        table
+    }
+
+    /// Define (or redefine) a unit under its label.
+    pub fn define(&mut self, unit: Unit) -> Option<Unit> {
+        self.insert(unit, false)
+    }
+
+    /// Define a unit that also accepts SI prefixes.
+    pub fn define_prefixable(&mut self, unit: Unit) -> Option<Unit> {
+        self.insert(unit, true)
+    }
+
+    fn insert(&mut self, unit: Unit, prefixable: bool) -> Option<Unit> {
+        let entry = Entry { unit, prefixable };
+        self.units.insert(entry.unit.label.clone(), entry).map(|old| old.unit)
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Unit> {
+        self.units.remove(name).map(|entry| entry.unit)
+    }
+
+    /// Look up `name`, trying it as a prefixed unit if there is no unit of that name.
+    pub fn get(&self, name: &str) -> Option<Unit> {
+        if let Some(entry) = self.units.get(name) {
+            return Some(entry.unit.clone());
+        }
+        PREFIXES.iter().find_map(|(prefix, exp)| {
+            let entry = self.units.get(name.strip_prefix(prefix)?).filter(|entry| entry.prefixable)?;
+            let scale = Pow::pow(BigRational::from_integer(BigInt::from(10)), *exp);
+            Some(Unit::new(name, &entry.unit.factor * scale, entry.unit.dim))
+        })
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+}
+
+/// The exact value of a decimal.
+pub fn decimal_to_rational(value: &BigDecimal) -> BigRational {
+    let (digits, scale) = value.as_bigint_and_exponent();
+    let ten = BigRational::from_integer(BigInt::from(10));
+    BigRational::from_integer(digits) / Pow::pow(ten, scale)
+}